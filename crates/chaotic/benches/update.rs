@@ -0,0 +1,72 @@
+use bevy::math::DVec2;
+use chaotic::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+
+const GRID_SIZES: [usize; 3] = [16, 64, 128];
+
+fn three_bodies() -> NBody {
+    NBody::new(
+        1.0,
+        vec![
+            Body::new(0.1, DVec2::X, DVec2::ZERO),
+            Body::new(0.1, DVec2::Y, DVec2::ZERO),
+            Body::new(0.1, DVec2::NEG_X, DVec2::ZERO),
+        ],
+        NBodyColorSchema::VelocityToRgb { v0: 1.0 },
+    )
+}
+
+fn mandelbrot() -> Mandelbrot {
+    let mut system = Mandelbrot::new(MandelbrotColorSchema::Distance);
+    system.mutate(&[-0.8, 0.0]);
+    system
+}
+
+/// Throughput of a whole grid update, in samples per second.
+fn bench_samples_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("samples_update");
+
+    for size in GRID_SIZES {
+        let dimensions = Dimensions::new(vec![size, size]);
+        group.throughput(Throughput::Elements(dimensions.volume() as u64));
+
+        let mut samples = Samples::new(three_bodies(), dimensions.clone(), &[1.0; 12], 0.01);
+        group.bench_with_input(BenchmarkId::new("NBody", size), &size, |b, _| {
+            b.iter(|| samples.update(1, black_box(0.01)))
+        });
+
+        let mut samples = Samples::new(mandelbrot(), dimensions, &[1.0, 1.0], 0.01);
+        group.bench_with_input(BenchmarkId::new("Mandelbrot", size), &size, |b, _| {
+            b.iter(|| samples.update(1, black_box(0.01)))
+        });
+    }
+
+    group.finish();
+}
+
+/// Cost of a single [`ChaoticSystem::update`] of every system.
+fn bench_system_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("system_update");
+
+    fn bench<S: ChaoticSystem>(
+        group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+        mut system: S,
+    ) {
+        group.bench_function(S::name(), |b| b.iter(|| system.update(black_box(0.01))));
+    }
+
+    bench(&mut group, three_bodies());
+    bench(&mut group, mandelbrot());
+    bench(&mut group, Bogdanov::canonical());
+    bench(&mut group, DeJong::canonical());
+    bench(&mut group, Tinkerbell::canonical());
+    bench(&mut group, Ikeda::new(0.9));
+    bench(&mut group, Thomas::new(0.208));
+    bench(&mut group, ChuaCircuit::double_scroll());
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_samples_update, bench_system_update);
+criterion_main!(benches);
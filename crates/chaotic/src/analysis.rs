@@ -0,0 +1,478 @@
+use crate::*;
+use bevy::math::DVec2;
+use image::{Rgba, RgbaImage};
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+
+/// Runs a clone of `system` for `steps` updates of `dt` and records the state after each step.
+pub fn trajectory<S: ChaoticSystem + Clone>(system: &S, steps: usize, dt: f64) -> Vec<S> {
+    let mut system = system.clone();
+    let mut states = Vec::with_capacity(steps);
+
+    for _ in 0..steps {
+        system.update_n(1, dt);
+        states.push(system.clone());
+    }
+
+    states
+}
+
+/// Renders the density of the orbit of `system` projected onto the first two values of
+/// [`ChaoticSystem::state_vector`], the classic way of imaging attractors.
+///
+/// `bounds` are the `(min, max)` corners of the imaged region, with `min` at the bottom left.
+/// Visited points are accumulated into a histogram and the hit count of every pixel is
+/// log-scaled into a gray level, so the most visited pixel is white and unvisited ones black.
+pub fn render_trajectory<S: ChaoticSystem + Clone>(
+    system: &S,
+    steps: usize,
+    dt: f64,
+    width: u32,
+    height: u32,
+    bounds: (DVec2, DVec2),
+) -> RgbaImage {
+    let (min, max) = bounds;
+    let size = max - min;
+    let mut hits = vec![0u32; width as usize * height as usize];
+
+    let mut system = system.clone();
+    for _ in 0..steps {
+        system.update_n(1, dt);
+
+        let state = system.state_vector();
+        let (Some(&x), Some(&y)) = (state.first(), state.get(1)) else {
+            continue;
+        };
+        let x = (x - min.x) / size.x * width as f64;
+        let y = (max.y - y) / size.y * height as f64;
+        if !(0.0..width as f64).contains(&x) || !(0.0..height as f64).contains(&y) {
+            continue;
+        }
+        hits[y as usize * width as usize + x as usize] += 1;
+    }
+
+    density_image(&hits, width, height)
+}
+
+/// Log-scales a row-major histogram of `width × height` hit counts into a gray image, so the most
+/// hit pixel is white and pixels without hits are black.
+pub(crate) fn density_image(hits: &[u32], width: u32, height: u32) -> RgbaImage {
+    let max_log = (hits.iter().copied().max().unwrap_or(0) as f64).ln_1p();
+    RgbaImage::from_fn(width, height, |x, y| {
+        let count = hits[y as usize * width as usize + x as usize];
+        let value = if count == 0 {
+            0.0
+        } else {
+            (count as f64).ln_1p() / max_log
+        };
+        let level = (value * 255.0).round() as u8;
+        Rgba([level, level, level, u8::MAX])
+    })
+}
+
+/// Estimates the largest Lyapunov exponent of `system` with the two-trajectory method.
+///
+/// A copy is perturbed by `delta0` along the first parameter of [`ChaoticSystem::mutate`], both
+/// are stepped side by side, and after every step the copy is pulled back (with
+/// [`ChaoticSystem::lerp`]) to the initial separation while the log-growth is accumulated. If
+/// the trajectories merge, the copy is perturbed again and the step is left out of the average.
+/// Positive values mean chaos, non-positive values mean the trajectories don't diverge.
+pub fn largest_lyapunov<S: ChaoticSystem + Clone>(
+    system: &S,
+    steps: usize,
+    dt: f64,
+    delta0: f64,
+) -> f64 {
+    let mut reference = system.clone();
+    let mut perturbed = system.clone();
+    perturbed.mutate(&[delta0]);
+
+    let mut separation = reference.distance(&perturbed);
+    if separation <= 0.0 {
+        separation = delta0.abs();
+    }
+
+    let mut log_growth = 0.0;
+    let mut completed_steps = 0;
+    for _ in 0..steps {
+        reference.update_n(1, dt);
+        perturbed.update_n(1, dt);
+
+        let distance = reference.distance(&perturbed);
+        if !distance.is_finite() {
+            break;
+        }
+        if distance <= 0.0 {
+            // Trajectories merged, there is no direction left to measure the growth along
+            perturbed = reference.clone();
+            perturbed.mutate(&[delta0]);
+            continue;
+        }
+
+        completed_steps += 1;
+        log_growth += (distance / separation).ln();
+        perturbed = reference.lerp(&perturbed, separation / distance);
+    }
+
+    if completed_steps == 0 {
+        return 0.0;
+    }
+
+    log_growth / (completed_steps as f64 * dt)
+}
+
+/// Looks for chaotic configurations of `base`: scores `samples` mutations spread over the box
+/// `param_ranges` (one `(min, max)` range per parameter of [`ChaoticSystem::mutate`]) with
+/// [`largest_lyapunov`] over `steps` updates of `dt`, and returns them most chaotic first.
+/// Mutations with a non-finite score are dropped.
+///
+/// The box is sampled with the quasi-random additive sequence of the generalized golden ratio,
+/// which covers it more evenly than independent random points and gives the same results on every
+/// run.
+pub fn find_interesting<S: ChaoticSystem + Clone>(
+    base: &S,
+    param_ranges: &[(f64, f64)],
+    samples: usize,
+    dt: f64,
+    steps: usize,
+) -> Vec<(Vec<f64>, f64)> {
+    // Root of `x^(d + 1) = x + 1`, whose powers give the per-axis steps of the sequence
+    let mut phi: f64 = 2.0;
+    for _ in 0..32 {
+        phi = (1.0 + phi).powf(1.0 / (param_ranges.len() + 1) as f64);
+    }
+
+    let mut found: Vec<_> = (0..samples)
+        .map(|n| {
+            let mutation: Vec<f64> = param_ranges
+                .iter()
+                .enumerate()
+                .map(|(axis, &(min, max))| {
+                    let step = phi.powi(-(axis as i32 + 1));
+                    lerp_f64(min, max, (0.5 + n as f64 * step).fract())
+                })
+                .collect();
+
+            let mut system = base.clone();
+            system.mutate(&mutation);
+            let score = largest_lyapunov(&system, steps, dt, 1e-8);
+            (mutation, score)
+        })
+        .filter(|(_, score)| score.is_finite())
+        .collect();
+
+    found.sort_by(|a, b| b.1.total_cmp(&a.1));
+    found
+}
+
+/// Relative tolerance of the repetition check in [`detect_period`].
+const PERIOD_TOLERANCE: f64 = 1e-6;
+/// Share of the spectrum peak a frequency needs to give a period candidate in [`detect_period`].
+const CANDIDATE_THRESHOLD: f64 = 1e-3;
+
+/// Detects the period (in steps) of the orbit of `system`, `None` if it doesn't settle on one.
+///
+/// The first half of `steps` is skipped as a transient. The rest is reduced to the norms of
+/// [`ChaoticSystem::state_vector`], every strong frequency of its spectrum gives a period
+/// candidate, and the shortest one for which the series matches itself shifted by the period is
+/// returned. Fixed points have period `1`.
+pub fn detect_period<S: ChaoticSystem + Clone>(system: &S, steps: usize, dt: f64) -> Option<usize> {
+    let series = trajectory(system, steps, dt)
+        .iter()
+        .skip(steps / 2)
+        .map(|state| {
+            state
+                .state_vector()
+                .iter()
+                .map(|v| v * v)
+                .sum::<f64>()
+                .sqrt()
+        })
+        .collect::<Vec<_>>();
+    if series.len() < 4 || !series.iter().all(|v| v.is_finite()) {
+        return None;
+    }
+
+    // Power-of-two length keeps the common period-doubling periods on exact frequency bins
+    let len = 1 << series.len().ilog2();
+    let series = &series[series.len() - len..];
+
+    let scale = series.iter().fold(0.0, |max: f64, v| max.max(v.abs())) + 1.0;
+    let repeats_after = |period: usize| {
+        series
+            .iter()
+            .zip(&series[period..])
+            .all(|(a, b)| (a - b).abs() <= PERIOD_TOLERANCE * scale)
+    };
+    if repeats_after(1) {
+        return Some(1);
+    }
+
+    let mean = series.iter().sum::<f64>() / len as f64;
+    let mut spectrum = series
+        .iter()
+        .map(|&v| Complex::new(v - mean, 0.0))
+        .collect::<Vec<_>>();
+    FftPlanner::new()
+        .plan_fft_forward(len)
+        .process(&mut spectrum);
+
+    let power = spectrum[1..=len / 2]
+        .iter()
+        .map(|c| c.norm_sqr())
+        .collect::<Vec<_>>();
+    let peak = power.iter().copied().fold(0.0, f64::max);
+
+    // Highest frequencies first, so the shortest repeating period wins over its multiples
+    (1..=power.len())
+        .rev()
+        .filter(|&frequency| power[frequency - 1] >= peak * CANDIDATE_THRESHOLD)
+        .map(|frequency| (len as f64 / frequency as f64).round() as usize)
+        .find(|&period| period < len && repeats_after(period))
+}
+
+/// Follows the attractor of `base` as the parameter `param_index` of [`ChaoticSystem::mutate`]
+/// sweeps `steps` evenly spaced values from `from` to `to`, the data behind a bifurcation diagram.
+///
+/// Values are mutations applied to a clone of `base`, which is then settled for `settle` updates
+/// of `dt` and its [`ChaoticSystem::state_vector`] is recorded next to the value.
+pub fn continuation<S: ChaoticSystem + Clone>(
+    base: &S,
+    param_index: usize,
+    from: f64,
+    to: f64,
+    steps: usize,
+    settle: usize,
+    dt: f64,
+) -> Vec<(f64, Vec<f64>)> {
+    let mut mutation = vec![0.0; base.param_count().max(param_index + 1)];
+
+    (0..steps)
+        .map(|step| {
+            let t = if steps > 1 {
+                step as f64 / (steps - 1) as f64
+            } else {
+                0.0
+            };
+            let value = lerp_f64(from, to, t);
+
+            let mut system = base.clone();
+            mutation[param_index] = value;
+            system.mutate(&mutation);
+            system.update_n(settle, dt);
+
+            (value, system.state_vector())
+        })
+        .collect()
+}
+
+/// Aggregate of a per-sample metric over a grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleStats {
+    /// Minimum, maximum and mean of the metric over finite samples, `NaN` if there are none.
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Share of samples whose state vector and metric are finite.
+    pub finite_fraction: f64,
+    /// Number of samples reporting [`ChaoticSystem::escaped`].
+    pub escaped: usize,
+}
+
+pub fn sample_stats<S: ChaoticSystem>(
+    samples: &Samples<S>,
+    metric: impl Fn(&S) -> f64,
+) -> SampleStats {
+    let mut min = f64::INFINITY;
+    let mut max = f64::NEG_INFINITY;
+    let mut sum = 0.0;
+    let mut finite = 0;
+    let mut escaped = 0;
+
+    for system in &samples.samples {
+        if system.escaped() {
+            escaped += 1;
+        }
+
+        let value = metric(system);
+        if !value.is_finite() || !system.state_vector().iter().all(|v| v.is_finite()) {
+            continue;
+        }
+        min = min.min(value);
+        max = max.max(value);
+        sum += value;
+        finite += 1;
+    }
+
+    if finite == 0 {
+        min = f64::NAN;
+        max = f64::NAN;
+    }
+
+    SampleStats {
+        min,
+        max,
+        mean: sum / finite as f64,
+        finite_fraction: finite as f64 / samples.samples.len().max(1) as f64,
+        escaped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{Decay, Driven};
+
+    #[test]
+    fn test_trajectory() {
+        let system = Decay::new(2.0);
+        let dt = 0.1;
+
+        let states = trajectory(&system, 50, dt);
+        assert_eq!(states.len(), 50);
+
+        for (step, state) in states.iter().enumerate() {
+            // Explicit Euler solution of x' = -x
+            let expected = 2.0 * (1.0 - dt).powi(step as i32 + 1);
+            assert!((state.value - expected).abs() < 1e-12);
+            assert_eq!(state.state_vector(), vec![state.value]);
+        }
+
+        // The original system is left untouched
+        assert_eq!(system.updates, 0);
+    }
+
+    #[test]
+    fn test_render_trajectory_fixed_point() {
+        // `c = 0` keeps `z` at the origin forever
+        let system = Mandelbrot::new(MandelbrotColorSchema::Distance);
+        let bounds = (DVec2::splat(-1.0), DVec2::splat(1.0));
+
+        let image = render_trajectory(&system, 100, 1.0, 5, 5, bounds);
+        assert_eq!(image.dimensions(), (5, 5));
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let expected = if (x, y) == (2, 2) { u8::MAX } else { 0 };
+            assert_eq!(
+                pixel.0,
+                [expected, expected, expected, u8::MAX],
+                "{}, {}",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn test_helpers_advance_time() {
+        let system = Driven::new(1.0);
+        let states = trajectory(&system, 5, 0.1);
+        assert!((states[4].time - 0.5).abs() < 1e-12, "{}", states[4].time);
+    }
+
+    #[test]
+    fn test_lyapunov_fixed_point() {
+        let exponent = largest_lyapunov(&Decay::new(1.0), 1000, 0.01, 1e-8);
+        assert!(exponent <= 0.0, "{}", exponent);
+    }
+
+    #[test]
+    fn test_lyapunov_merged_trajectories() {
+        // Decays to exactly zero in a single step, so the trajectories merge on every step
+        let exponent = largest_lyapunov(&Decay::new(1.0), 100, 1.0, 1e-8);
+        assert_eq!(exponent, 0.0);
+
+        // Without merges, the separation halves on every step
+        let exponent = largest_lyapunov(&Decay::new(1.0), 100, 0.5, 1e-8);
+        assert!((exponent - 2.0 * 0.5f64.ln()).abs() < 1e-6, "{}", exponent);
+    }
+
+    #[test]
+    fn test_lyapunov_chaotic_nbody() {
+        let system = NBody::new(
+            1.0,
+            vec![
+                Body::new(1.0, DVec2::new(1.0, 0.0), DVec2::new(0.0, 0.3)),
+                Body::new(1.0, DVec2::new(-0.5, 0.8), DVec2::new(-0.2, -0.1)),
+                Body::new(1.0, DVec2::new(-0.4, -0.7), DVec2::new(0.25, -0.15)),
+            ],
+            NBodyColorSchema::VelocityToRgb { v0: 1.0 },
+        );
+
+        let exponent = largest_lyapunov(&system, 2000, 0.01, 1e-8);
+        assert!(exponent > 0.0, "{}", exponent);
+    }
+
+    #[test]
+    fn test_sample_stats() {
+        let values = [0.0, 1.0, 2.0, f64::INFINITY];
+        let samples = Samples::from_vec(
+            Dimensions::new(vec![2, 2]),
+            values.iter().map(|&value| Decay::new(value)).collect(),
+        );
+
+        let stats = sample_stats(&samples, |system| system.value);
+        assert_eq!(
+            stats,
+            SampleStats {
+                min: 0.0,
+                max: 2.0,
+                mean: 1.0,
+                finite_fraction: 0.75,
+                escaped: 0,
+            }
+        );
+
+        let empty = sample_stats(&samples, |_| f64::NAN);
+        assert!(empty.mean.is_nan() && empty.min.is_nan());
+        assert_eq!(empty.finite_fraction, 0.0);
+    }
+
+    #[test]
+    fn test_detect_period_logistic() {
+        assert_eq!(detect_period(&LogisticMap::new(2.8), 2048, 1.0), Some(1));
+        assert_eq!(detect_period(&LogisticMap::new(3.2), 2048, 1.0), Some(2));
+        assert_eq!(detect_period(&LogisticMap::new(3.5), 2048, 1.0), Some(4));
+        assert_eq!(detect_period(&LogisticMap::new(3.83), 2048, 1.0), Some(3));
+        assert_eq!(detect_period(&LogisticMap::new(3.9), 2048, 1.0), None);
+    }
+
+    #[test]
+    fn test_continuation_period_doubling() {
+        let results = continuation(&LogisticMap::new(0.0), 0, 2.8, 3.2, 2, 1000, 1.0);
+        assert_eq!(results.len(), 2);
+        assert_eq!((results[0].0, results[1].0), (2.8, 3.2));
+
+        // Fixed point `1 - 1/r` before the first doubling
+        let fixed = results[0].1[0];
+        assert!((fixed - (1.0 - 1.0 / 2.8)).abs() < 1e-9, "{}", fixed);
+
+        // One of the two points of the 2-cycle after it
+        let r: f64 = 3.2;
+        let root = ((r + 1.0) * (r - 3.0)).sqrt();
+        let cycle = [(r + 1.0 - root) / (2.0 * r), (r + 1.0 + root) / (2.0 * r)];
+        let x = results[1].1[0];
+        assert!(cycle.iter().any(|point| (x - point).abs() < 1e-9), "{}", x);
+
+        let single = continuation(&LogisticMap::new(0.0), 0, 2.8, 3.2, 1, 1000, 1.0);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].0, 2.8);
+    }
+
+    #[test]
+    fn test_find_interesting_logistic() {
+        let found = find_interesting(&LogisticMap::new(0.0), &[(2.5, 4.0)], 32, 1.0, 2000);
+        assert_eq!(found.len(), 32);
+        assert!(found.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        let (best, score) = &found[0];
+        assert!(best[0] > 3.57 && *score > 0.0, "{:?}", found[0]);
+
+        // Values before the onset of chaos converge to cycles and rank below the best ones
+        let chaotic = found.iter().position(|(r, _)| r[0] > 3.57).unwrap();
+        for (index, (r, score)) in found.iter().enumerate() {
+            if r[0] < 2.9 {
+                assert!(index > chaotic && *score < 0.0, "{} {}", r[0], score);
+            }
+        }
+    }
+}
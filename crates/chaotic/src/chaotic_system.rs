@@ -1,12 +1,85 @@
 use bevy::color::Color;
+use bevy::math::DVec2;
+
+/// Meaning of one value accepted by [`ChaoticSystem::mutate`], see
+/// [`ChaoticSystem::mutation_descriptor`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MutationParam {
+    pub label: String,
+    /// Suggested mutation scale of a grid axis sweeping this parameter.
+    pub scale: f64,
+    /// Range of mutations giving meaningful systems, `None` if there are no limits.
+    pub range: Option<(f64, f64)>,
+}
+
+impl MutationParam {
+    pub fn new(label: impl Into<String>, scale: f64) -> Self {
+        MutationParam {
+            label: label.into(),
+            scale,
+            range: None,
+        }
+    }
+
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+}
 
 pub trait ChaoticSystem: Send + Sync + 'static {
+    /// Human-readable name of the system.
+    fn name() -> &'static str
+    where
+        Self: Sized;
+
+    /// Time step the system runs well with, passed to [`ChaoticSystem::update`]. Maps ignore the
+    /// time step and suggest `1`.
+    fn suggested_dt() -> f64
+    where
+        Self: Sized;
+
+    /// Number of values accepted by [`ChaoticSystem::mutate`].
+    fn param_count(&self) -> usize;
+
+    /// Labels of the values accepted by [`ChaoticSystem::mutate`], one per parameter.
+    fn param_labels(&self) -> Vec<String>;
+
+    /// Describes every value accepted by [`ChaoticSystem::mutate`], one per parameter. Defaults to
+    /// the [`ChaoticSystem::param_labels`] with unit scales and no ranges.
+    fn mutation_descriptor(&self) -> Vec<MutationParam> {
+        self.param_labels()
+            .into_iter()
+            .map(|label| MutationParam::new(label, 1.0))
+            .collect()
+    }
+
     /// Mutates the system by a `mutation` factor.
     fn mutate(&mut self, pos: &[f64]);
 
+    /// Resets the dynamical state to its starting value, keeping the parameters.
+    ///
+    /// Systems whose state is also their mutated initial condition (like [`crate::NBody`]) keep
+    /// their current state.
+    fn reset_state(&mut self) {}
+
     /// Updates the system state by a time step `dt`.
     fn update(&mut self, dt: f64);
 
+    /// Calls [`ChaoticSystem::update`] and [`ChaoticSystem::advance_time`] `n` times. Systems
+    /// with a cheaper way of taking several steps at once can override it.
+    fn update_n(&mut self, n: usize, dt: f64) {
+        for _ in 0..n {
+            self.update(dt);
+            self.advance_time(dt);
+        }
+    }
+
+    /// Called after every update by [`ChaoticSystem::update_n`] (and so by [`crate::Samples`]).
+    /// Systems with a time-dependent forcing term override it to accumulate the elapsed time,
+    /// others ignore it.
+    fn advance_time(&mut self, _dt: f64) {}
+
     /// Creates a new system instance by interpolating between `self` and `other` at a factor `t`
     /// (between `0` and `1`).
     fn lerp(&self, other: &Self, t: f64) -> Self;
@@ -14,6 +87,99 @@ pub trait ChaoticSystem: Send + Sync + 'static {
     /// Returns the RGB color representation of the system.
     fn color(&self) -> Color;
 
+    /// Color between the colors of `self` and `other` at a factor `t`, blended in Oklab (see
+    /// [`crate::mix_oklab`]) to avoid the dark midtones of RGB blending.
+    fn lerp_color(&self, other: &Self, t: f64) -> Color {
+        crate::mix_oklab(self.color(), other.color(), t)
+    }
+
+    /// Opacity in `[0, 1]` renderers can multiply into the alpha of [`ChaoticSystem::color`], low
+    /// for stable samples so chaotic ones stand out when layers are blended. Opaque by default.
+    fn alpha(&self) -> f32 {
+        1.0
+    }
+
     /// Returns a difference value between two systems.
     fn distance(&self, other: &Self) -> f64;
+
+    /// Returns the dynamical state of the system as a flat list of values.
+    fn state_vector(&self) -> Vec<f64>;
+
+    /// Whether the orbit is known to have escaped to infinity, like `|z| > 2` for
+    /// [`crate::Mandelbrot`]. Systems without an escape criterion never escape.
+    fn escaped(&self) -> bool {
+        false
+    }
+
+    /// `(min, max)` corners of the 2D region the system occupies in its own space, `None` for
+    /// systems without a spatial interpretation.
+    fn spatial_bounds(&self) -> Option<(DVec2, DVec2)> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::Decay;
+    use crate::*;
+
+    #[test]
+    fn test_update_n_repeats_update() {
+        let mut batched = Decay::new(3.0);
+        batched.update_n(3, 0.1);
+
+        let mut manual = Decay::new(3.0);
+        for _ in 0..3 {
+            manual.update(0.1);
+        }
+
+        assert_eq!(batched.value, manual.value);
+        assert_eq!(batched.updates, 3);
+    }
+
+    fn assert_runs_stably<T: ChaoticSystem + Clone>(system: T) {
+        let dt = T::suggested_dt();
+        assert!(dt > 0.0 && dt <= 1.0, "{}: {}", T::name(), dt);
+
+        let mutation_scales = vec![1e-6; system.param_count()];
+        let mut samples = Samples::new(system, Dimensions::new(vec![3, 3]), &mutation_scales, 1.0);
+        samples.update(500, dt);
+        for (pos, sample) in samples.iter() {
+            let finite = sample.state_vector().iter().all(|value| value.is_finite());
+            assert!(finite, "{} at {:?}", T::name(), pos);
+        }
+    }
+
+    #[test]
+    fn test_suggested_dt() {
+        // Maps ignore the time step, flows need small ones
+        assert_eq!(Mandelbrot::suggested_dt(), 1.0);
+        assert_eq!(LogisticMap::suggested_dt(), 1.0);
+        assert!(ChuaCircuit::suggested_dt() < 0.1);
+        assert!(Thomas::suggested_dt() < 0.1);
+
+        assert_runs_stably(ArnoldCat::new(DVec2::new(0.3, 0.7)));
+        assert_runs_stably(Bogdanov::canonical());
+        assert_runs_stably(ChuaCircuit::double_scroll());
+        assert_runs_stably(CoupledMapLattice::new(
+            8,
+            3.9,
+            1.0 / 3.0,
+            CoupledMapColorSchema::Mean,
+        ));
+        assert_runs_stably(DeJong::canonical());
+        assert_runs_stably(GumowskiMira::new(0.3, 1.0));
+        assert_runs_stably(Ikeda::new(0.9));
+        assert_runs_stably(LogisticMap::new(3.9));
+        assert_runs_stably(Lozi::canonical());
+        assert_runs_stably(MagneticPendulum::three_magnets());
+        assert_runs_stably(Mandelbrot::new(MandelbrotColorSchema::Distance));
+        assert_runs_stably(NBody::ring(3, 1.0, 0.1, 1.0, 0.0));
+        assert_runs_stably(Pickover::canonical());
+        assert_runs_stably(QuadraticMap::new(2));
+        assert_runs_stably(Sprott::new(SprottVariant::A));
+        assert_runs_stably(Thomas::new(0.208));
+        assert_runs_stably(Tinkerbell::canonical());
+    }
 }
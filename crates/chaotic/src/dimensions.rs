@@ -1,7 +1,10 @@
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::ops::{Index, IndexMut};
 
-#[derive(Debug, Clone)]
+/// Sizes of the axes of a sample grid. Compared and hashed by the sizes, whether they are static
+/// or owned.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Dimensions {
     dimensions: Cow<'static, [usize]>,
 }
@@ -28,6 +31,17 @@ impl Dimensions {
         self.dimensions.len()
     }
 
+    /// Whether there are no dimensions at all
+    pub fn is_empty(&self) -> bool {
+        self.dimensions.is_empty()
+    }
+
+    /// Same dimensions with every empty axis grown to a single cell, so the grid has at least one
+    /// sample.
+    pub fn non_empty(&self) -> Self {
+        Dimensions::new(self.dimensions.iter().map(|&size| size.max(1)).collect())
+    }
+
     /// ND volume of the dimensions
     pub fn volume(&self) -> usize {
         self.dimensions.iter().product()
@@ -66,6 +80,39 @@ impl Dimensions {
 
         index
     }
+
+    /// Linear index step of each dimension, as used by [`Dimensions::pos_to_index`]
+    pub fn strides(&self) -> Vec<usize> {
+        let mut strides = Vec::with_capacity(self.dimensions.len());
+        let mut multiplier = 1;
+
+        for &dim_size in self.dimensions.iter() {
+            strides.push(multiplier);
+            multiplier *= dim_size;
+        }
+
+        strides
+    }
+
+    /// In-bounds positions differing from `pos` by one along a single dimension
+    pub fn neighbors(&self, pos: &[usize]) -> Vec<Vec<usize>> {
+        let mut neighbors = Vec::with_capacity(self.dimensions.len() * 2);
+
+        for (i, &dim_size) in self.dimensions.iter().enumerate() {
+            if pos[i] > 0 {
+                let mut neighbor = pos.to_vec();
+                neighbor[i] -= 1;
+                neighbors.push(neighbor);
+            }
+            if pos[i] + 1 < dim_size {
+                let mut neighbor = pos.to_vec();
+                neighbor[i] += 1;
+                neighbors.push(neighbor);
+            }
+        }
+
+        neighbors
+    }
 }
 
 impl Index<usize> for Dimensions {
@@ -232,6 +279,28 @@ mod tests {
         assert_eq!(dims.index_to_pos(5), vec![1, 2]);
     }
 
+    #[test]
+    fn test_static_owned_equality() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash(dims: &Dimensions) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            dims.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        static DIMS: &[usize] = &[4, 5, 6];
+        let borrowed = Dimensions::new_static(DIMS);
+        let owned = Dimensions::new(vec![4, 5, 6]);
+        assert_eq!(borrowed, owned);
+        assert_eq!(hash(&borrowed), hash(&owned));
+
+        let other = Dimensions::new(vec![4, 5, 7]);
+        assert_ne!(borrowed, other);
+        assert_ne!(Dimensions::new(vec![4, 5]), owned);
+    }
+
     #[test]
     fn test_volume_calculation() {
         let dims_1d = Dimensions::new(vec![5]);
@@ -252,4 +321,40 @@ mod tests {
             assert_eq!(pos, expected_pos, "Mismatch at index {}", index);
         }
     }
+
+    #[test]
+    fn test_strides() {
+        let dims = Dimensions::new(vec![2, 3, 4]);
+        assert_eq!(dims.strides(), vec![1, 2, 6]);
+
+        for pos in dims.iter() {
+            let index = pos
+                .iter()
+                .zip(dims.strides())
+                .map(|(c, s)| c * s)
+                .sum::<usize>();
+            assert_eq!(index, dims.pos_to_index(&pos));
+        }
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let dims = Dimensions::new(vec![3, 4]);
+
+        // Interior
+        let mut neighbors = dims.neighbors(&[1, 1]);
+        neighbors.sort();
+        assert_eq!(
+            neighbors,
+            vec![vec![0, 1], vec![1, 0], vec![1, 2], vec![2, 1]]
+        );
+
+        // Edge
+        assert_eq!(dims.neighbors(&[1, 0]).len(), 3);
+        assert_eq!(dims.neighbors(&[2, 2]).len(), 3);
+
+        // Corners
+        assert_eq!(dims.neighbors(&[0, 0]).len(), 2);
+        assert_eq!(dims.neighbors(&[2, 3]).len(), 2);
+    }
 }
@@ -1,9 +1,14 @@
+mod analysis;
 mod chaotic_system;
 mod dimensions;
 mod sample;
 mod systems;
 mod utils;
 
+#[cfg(test)]
+mod test_utils;
+
+pub use analysis::*;
 pub use chaotic_system::*;
 pub use dimensions::*;
 pub use sample::*;
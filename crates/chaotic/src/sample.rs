@@ -1,11 +1,110 @@
 use crate::*;
+use bevy::color::{Color, LinearRgba};
+use bevy::math::DVec2;
+use image::RgbaImage;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 
+/// Cell of a [`Samples`] grid holding the unmutated initial system.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GridOrigin {
+    /// The first cell, so every mutation is non-negative.
+    Corner,
+    /// `size / 2` along every axis.
+    #[default]
+    Center,
+    /// Given grid coordinates, missing ones default to the center. Clamped to the grid.
+    Custom(Vec<usize>),
+}
+
+impl GridOrigin {
+    /// Grid coordinates of the origin cell.
+    pub fn cell(&self, dimensions: &Dimensions) -> Vec<usize> {
+        dimensions
+            .sizes()
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| {
+                let cord = match self {
+                    GridOrigin::Corner => 0,
+                    GridOrigin::Center => size / 2,
+                    GridOrigin::Custom(pos) => pos.get(i).copied().unwrap_or(size / 2),
+                };
+                cord.min(size.saturating_sub(1))
+            })
+            .collect()
+    }
+}
+
+/// Direction the mutation grows along a grid axis, away from the [`GridOrigin`] cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AxisDirection {
+    /// Mutation grows with the grid coordinate.
+    #[default]
+    Positive,
+    /// Mutation shrinks as the grid coordinate grows, mirroring the axis around the origin.
+    Negative,
+}
+
+impl AxisDirection {
+    /// Direction of `axis` in `directions`, missing ones are [`AxisDirection::Positive`].
+    pub fn of_axis(directions: &[AxisDirection], axis: usize) -> Self {
+        directions.get(axis).copied().unwrap_or_default()
+    }
+
+    pub fn sign(self) -> f64 {
+        match self {
+            AxisDirection::Positive => 1.0,
+            AxisDirection::Negative => -1.0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Samples<T> {
     pub dimensions: Dimensions,
     pub samples: Vec<T>,
+    /// Per-sample flag of whether the sample still evolves, used by [`Samples::update_active`].
+    /// Empty until the first call, meaning every sample is active.
+    #[serde(default)]
+    active: Vec<bool>,
+    /// Last computed color of every sample, `None` if outdated. Disabled if empty, see
+    /// [`Samples::enable_color_cache`]. Not serialized, so disabled after deserializing.
+    #[serde(skip)]
+    color_cache: Vec<Option<Color>>,
+    /// Per-sample number of updates since the sample was last [perturbed](Samples::perturb) or
+    /// resumed after being frozen by [`Samples::update_active`], see [`Samples::fade_in_alpha`].
+    /// Samples that never were count as old, `u32::MAX`.
+    #[serde(default)]
+    age: Vec<u32>,
+    /// States before the last update, used by [`Samples::velocity_image`]. Disabled if empty, see
+    /// [`Samples::enable_history`].
+    #[serde(skip, default = "Vec::new")]
+    previous: Vec<T>,
 }
 
 impl<System> Samples<System> {
+    /// Wraps already built samples, `samples` must be ordered by [`Dimensions::index_to_pos`].
+    pub fn from_vec(dimensions: Dimensions, samples: Vec<System>) -> Self {
+        assert_eq!(
+            dimensions.volume(),
+            samples.len(),
+            "Sample count doesn't match dimensions"
+        );
+
+        Samples {
+            dimensions,
+            samples,
+            active: Vec::new(),
+            color_cache: Vec::new(),
+            age: Vec::new(),
+            previous: Vec::new(),
+        }
+    }
+
+    /// Samples mutated relative to the grid center, see [`Samples::new_with_origin`].
     pub fn new(
         initial_system: System,
         dimensions: Dimensions,
@@ -15,15 +114,47 @@ impl<System> Samples<System> {
     where
         System: ChaoticSystem + Clone,
     {
+        Self::new_with_origin(
+            initial_system,
+            dimensions,
+            mutation_scales,
+            &[],
+            &[],
+            all_scale,
+            &GridOrigin::Center,
+        )
+    }
+
+    /// Builds a grid of mutated copies of `initial_system`, the cell at `origin` is only mutated
+    /// by `offsets`. Every axis is shifted by its offset, missing offsets are `0`, and swept in
+    /// its direction from `directions`, see [`AxisDirection::of_axis`]. Empty axes of `dimensions`
+    /// are grown to a single cell, see [`Dimensions::non_empty`].
+    pub fn new_with_origin(
+        initial_system: System,
+        dimensions: Dimensions,
+        mutation_scales: &[f64],
+        offsets: &[f64],
+        directions: &[AxisDirection],
+        all_scale: f64,
+        origin: &GridOrigin,
+    ) -> Self
+    where
+        System: ChaoticSystem + Clone,
+    {
+        let dimensions = dimensions.non_empty();
         let mut samples = Vec::with_capacity(dimensions.volume());
+        let origin = origin.cell(&dimensions);
 
         for pos in dimensions.iter() {
             let mutation = pos
                 .into_iter()
                 .zip(mutation_scales)
-                .zip(dimensions.sizes())
-                .map(|((cord, scale), &size)| {
-                    (cord as f64 + -(size as f64) * 0.5) * scale * all_scale
+                .zip(&origin)
+                .enumerate()
+                .map(|(axis, ((cord, scale), &origin))| {
+                    let offset = offsets.get(axis).copied().unwrap_or_default();
+                    let sign = AxisDirection::of_axis(directions, axis).sign();
+                    (cord as f64 - origin as f64) * sign * scale * all_scale + offset
                 })
                 .collect::<Vec<_>>();
 
@@ -32,21 +163,256 @@ impl<System> Samples<System> {
             samples.push(system);
         }
 
-        Samples {
-            samples,
-            dimensions,
-        }
+        Samples::from_vec(dimensions, samples)
     }
 
     pub fn update(&mut self, iterations: usize, dt: f64)
     where
-        System: ChaoticSystem,
+        System: ChaoticSystem + Clone,
     {
+        self.remember_previous();
+        self.resume_frozen();
         for system in &mut self.samples {
-            for _ in 0..iterations {
-                system.update(dt);
+            system.update_n(iterations, dt);
+        }
+
+        for color in &mut self.color_cache {
+            *color = None;
+        }
+        self.grow_older();
+    }
+
+    /// Same as [`Samples::update`], but advances the whole grid one iteration at a time, calling
+    /// `progress` with the number of finished iterations after each. Stops early once
+    /// `max_duration` has passed, leaving every sample at the same iteration, and returns how many
+    /// iterations were finished.
+    pub fn update_with_progress(
+        &mut self,
+        iterations: usize,
+        dt: f64,
+        max_duration: Option<Duration>,
+        mut progress: impl FnMut(usize),
+    ) -> usize
+    where
+        System: ChaoticSystem + Clone,
+    {
+        self.remember_previous();
+        self.resume_frozen();
+        let start = Instant::now();
+        let mut finished = 0;
+
+        while finished < iterations {
+            for system in &mut self.samples {
+                system.update_n(1, dt);
+            }
+            finished += 1;
+            progress(finished);
+
+            if max_duration.is_some_and(|max| start.elapsed() >= max) {
+                break;
+            }
+        }
+
+        for color in &mut self.color_cache {
+            *color = None;
+        }
+        self.grow_older();
+        finished
+    }
+
+    /// Same as [`Samples::update`], but skips samples that have converged: once a sample moves less
+    /// than `tol` (by [`ChaoticSystem::distance`]) during an update, it is no longer updated.
+    pub fn update_active(&mut self, iterations: usize, dt: f64, tol: f64)
+    where
+        System: ChaoticSystem + Clone,
+    {
+        if self.active.len() != self.samples.len() {
+            self.active = vec![true; self.samples.len()];
+        }
+        self.remember_previous();
+
+        for (index, (system, active)) in self.samples.iter_mut().zip(&mut self.active).enumerate() {
+            if !*active {
+                continue;
+            }
+
+            let prev = system.clone();
+            system.update_n(iterations, dt);
+
+            if prev.distance(system) < tol {
+                *active = false;
+            }
+            if let Some(color) = self.color_cache.get_mut(index) {
+                *color = None;
+            }
+        }
+        self.grow_older();
+    }
+
+    /// Mutates the sample at `index` by `mutation` (see [`ChaoticSystem::mutate`]), making it
+    /// active again and resetting its age.
+    pub fn perturb(&mut self, index: usize, mutation: &[f64])
+    where
+        System: ChaoticSystem,
+    {
+        self.samples[index].mutate(mutation);
+        if let Some(active) = self.active.get_mut(index) {
+            *active = true;
+        }
+        if let Some(color) = self.color_cache.get_mut(index) {
+            *color = None;
+        }
+        self.age.resize(self.samples.len(), u32::MAX);
+        self.age[index] = 0;
+    }
+
+    /// Number of updates since the sample at `index` was last perturbed or resumed, `u32::MAX` if
+    /// it never was.
+    pub fn age(&self, index: usize) -> u32 {
+        self.age.get(index).copied().unwrap_or(u32::MAX)
+    }
+
+    /// Alpha ramp fading in a sample over the first `frames` updates after it was perturbed or
+    /// resumed, from `0` up to `1`. `0` frames disable the fade.
+    pub fn fade_in_alpha(&self, index: usize, frames: u32) -> f32 {
+        if frames == 0 {
+            return 1.0;
+        }
+        (self.age(index) as f32 / frames as f32).min(1.0)
+    }
+
+    /// Makes samples frozen by [`Samples::update_active`] evolve again, restarting their age.
+    fn resume_frozen(&mut self) {
+        if self.active.is_empty() {
+            return;
+        }
+
+        self.age.resize(self.samples.len(), u32::MAX);
+        for (age, &active) in self.age.iter_mut().zip(&self.active) {
+            if !active {
+                *age = 0;
             }
         }
+        self.active.clear();
+    }
+
+    fn grow_older(&mut self) {
+        self.age.resize(self.samples.len(), u32::MAX);
+        for age in &mut self.age {
+            *age = age.saturating_add(1);
+        }
+    }
+
+    /// Keeps the states before every update, see [`Samples::velocity_image`]. Costs a copy of the
+    /// whole grid per update.
+    pub fn enable_history(&mut self)
+    where
+        System: Clone,
+    {
+        self.previous = self.samples.clone();
+    }
+
+    fn remember_previous(&mut self)
+    where
+        System: Clone,
+    {
+        if !self.previous.is_empty() {
+            self.previous.clone_from(&self.samples);
+        }
+    }
+
+    /// Distance (by [`ChaoticSystem::distance`]) every sample moved during the last update,
+    /// normalized to `[0, 1]`, highlighting where the grid changes fastest. All zero until
+    /// [`Samples::enable_history`] is called and the samples are updated.
+    pub fn velocity_image(&self) -> Samples<f64>
+    where
+        System: ChaoticSystem,
+    {
+        if self.previous.len() != self.samples.len() {
+            return self.map(|_| 0.0);
+        }
+
+        let mut velocity = Samples::from_vec(
+            self.dimensions.clone(),
+            self.previous
+                .iter()
+                .zip(&self.samples)
+                .map(|(prev, system)| prev.distance(system))
+                .collect(),
+        );
+
+        let max = velocity.samples.iter().copied().fold(0.0, f64::max);
+        if max > 0.0 && max.is_finite() {
+            for value in &mut velocity.samples {
+                *value /= max;
+            }
+        }
+
+        velocity
+    }
+
+    /// Keeps computed colors until the sample is updated again, so samples skipped by
+    /// [`Samples::update_active`] aren't recolored. Filled by [`Samples::refresh_colors`].
+    pub fn enable_color_cache(&mut self) {
+        self.color_cache = vec![None; self.samples.len()];
+    }
+
+    /// Computes colors of the samples changed since the last call, if the cache is enabled.
+    pub fn refresh_colors(&mut self)
+    where
+        System: ChaoticSystem,
+    {
+        for (system, color) in self.samples.iter().zip(&mut self.color_cache) {
+            if color.is_none() {
+                *color = Some(system.color());
+            }
+        }
+    }
+
+    /// Color of the sample at `index`, taken from the cache when it's up to date.
+    pub fn sample_color(&self, index: usize) -> Color
+    where
+        System: ChaoticSystem,
+    {
+        match self.color_cache.get(index) {
+            Some(Some(color)) => *color,
+            _ => self.samples[index].color(),
+        }
+    }
+
+    /// Color of the sample at `index` averaged over `frames` states: the current one and those of a
+    /// clone advanced by `dt` after each. Smooths fast-changing colors between layers without
+    /// touching the sample itself.
+    pub fn color_averaged(&self, index: usize, frames: usize, dt: f64) -> Color
+    where
+        System: ChaoticSystem + Clone,
+    {
+        if frames <= 1 {
+            return self.sample_color(index);
+        }
+
+        let mut system = self.samples[index].clone();
+        let mut sum = LinearRgba::NONE;
+        for frame in 0..frames {
+            if frame > 0 {
+                system.update_n(1, dt);
+            }
+            sum += system.color().to_linear();
+        }
+        (sum / frames as f32).into()
+    }
+
+    /// Whether the sample at `index` is still updated by [`Samples::update_active`].
+    pub fn is_active(&self, index: usize) -> bool {
+        self.active.get(index).copied().unwrap_or(true)
+    }
+
+    /// Number of samples still updated by [`Samples::update_active`].
+    pub fn active_count(&self) -> usize {
+        if self.active.len() != self.samples.len() {
+            return self.samples.len();
+        }
+        self.active.iter().filter(|&&active| active).count()
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (Vec<usize>, &System)> {
@@ -55,4 +421,540 @@ impl<System> Samples<System> {
             .enumerate()
             .map(|(i, s)| (self.dimensions.index_to_pos(i), s))
     }
+
+    /// Parallel version of [`Samples::iter`], visiting samples in no particular order.
+    #[cfg(feature = "rayon")]
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = (Vec<usize>, &System)>
+    where
+        System: Sync,
+    {
+        self.samples
+            .par_iter()
+            .enumerate()
+            .map(|(i, s)| (self.dimensions.index_to_pos(i), s))
+    }
+
+    /// Creates new samples with the same dimensions by transforming every sample with `f`.
+    pub fn map<U>(&self, f: impl Fn(&System) -> U) -> Samples<U> {
+        Samples {
+            dimensions: self.dimensions.clone(),
+            samples: self.samples.iter().map(f).collect(),
+            active: self.active.clone(),
+            color_cache: Vec::new(),
+            age: self.age.clone(),
+            previous: Vec::new(),
+        }
+    }
+
+    /// Extracts the 2D plane spanned by `axis_x` and `axis_y`.
+    ///
+    /// `fixed` is a full position in the grid: its coordinates along the other axes select the
+    /// plane, the ones along `axis_x` and `axis_y` are ignored.
+    pub fn slice_2d(&self, axis_x: usize, axis_y: usize, fixed: &[usize]) -> Samples<System>
+    where
+        System: Clone,
+    {
+        assert_ne!(axis_x, axis_y, "Slice axes must differ");
+        assert_eq!(
+            fixed.len(),
+            self.dimensions.len(),
+            "Expected a coordinate for every dimension"
+        );
+
+        let dimensions = Dimensions::new(vec![self.dimensions[axis_x], self.dimensions[axis_y]]);
+        let mut pos = fixed.to_vec();
+        let mut samples = Vec::with_capacity(dimensions.volume());
+
+        for plane_pos in dimensions.iter() {
+            pos[axis_x] = plane_pos[0];
+            pos[axis_y] = plane_pos[1];
+            samples.push(self.samples[self.dimensions.pos_to_index(&pos)].clone());
+        }
+
+        Samples::from_vec(dimensions, samples)
+    }
+
+    /// Gradient magnitude of a scalar `metric` across the grid, normalized to `[0, 1]`.
+    ///
+    /// Uses central differences (one-sided at the grid edges), so sharp changes of the metric
+    /// between neighboring samples light up, highlighting basin boundaries.
+    pub fn edge_image(&self, metric: impl Fn(&System) -> f64) -> Samples<f64> {
+        let values = self.map(metric);
+        let strides = self.dimensions.strides();
+
+        let mut edges = values.map(|_| 0.0);
+        for (index, pos) in self.dimensions.iter().enumerate() {
+            let mut magnitude_sq = 0.0;
+
+            for (axis, (&coord, &stride)) in pos.iter().zip(&strides).enumerate() {
+                let low = if coord > 0 { index - stride } else { index };
+                let high = if coord + 1 < self.dimensions[axis] {
+                    index + stride
+                } else {
+                    index
+                };
+                if low == high {
+                    continue;
+                }
+
+                let steps = ((high - low) / stride) as f64;
+                let derivative = (values.samples[high] - values.samples[low]) / steps;
+                magnitude_sq += derivative * derivative;
+            }
+
+            edges.samples[index] = magnitude_sq.sqrt();
+        }
+
+        let max = edges.samples.iter().copied().fold(0.0, f64::max);
+        if max > 0.0 && max.is_finite() {
+            for value in &mut edges.samples {
+                *value /= max;
+            }
+        }
+
+        edges
+    }
+
+    /// Scatters every sample at `(x_fn, y_fn)` into a density image (see [`render_trajectory`]),
+    /// showing how the grid is distributed in phase space. The image spans the bounding box of all
+    /// finite points, with the largest `y` at the top.
+    pub fn phase_portrait_image(
+        &self,
+        width: u32,
+        height: u32,
+        x_fn: impl Fn(&System) -> f64,
+        y_fn: impl Fn(&System) -> f64,
+    ) -> RgbaImage {
+        let points = self
+            .samples
+            .iter()
+            .map(|sample| DVec2::new(x_fn(sample), y_fn(sample)))
+            .filter(|point| point.is_finite())
+            .collect::<Vec<_>>();
+
+        let (min, max) = points.iter().fold(
+            (DVec2::splat(f64::INFINITY), DVec2::splat(f64::NEG_INFINITY)),
+            |(min, max), &point| (min.min(point), max.max(point)),
+        );
+        // A single value along an axis lands in the middle
+        let size = max - min;
+        let size = DVec2::select(size.cmpgt(DVec2::ZERO), size, DVec2::ONE);
+        let min = DVec2::select((max - min).cmpgt(DVec2::ZERO), min, min - size / 2.0);
+
+        let mut hits = vec![0u32; width as usize * height as usize];
+        if width > 0 && height > 0 {
+            for point in points {
+                let x = (point.x - min.x) / size.x * width as f64;
+                let y = (min.y + size.y - point.y) / size.y * height as f64;
+                let x = (x as usize).min(width as usize - 1);
+                let y = (y as usize).min(height as usize - 1);
+                hits[y * width as usize + x] += 1;
+            }
+        }
+
+        density_image(&hits, width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{Decay, Driven};
+
+    fn decay_samples(value: f64) -> Samples<Decay> {
+        Samples::new(Decay::new(value), Dimensions::new(vec![3, 3]), &[0.0], 1.0)
+    }
+
+    #[test]
+    fn test_phase_portrait_quadrants() {
+        // Three samples in the top right quadrant, one in the bottom left
+        let points = vec![
+            DVec2::new(1.0, 1.0),
+            DVec2::new(0.9, 0.8),
+            DVec2::new(1.0, 1.0),
+            DVec2::new(-1.0, -1.0),
+        ];
+        let samples = Samples::from_vec(Dimensions::new(vec![2, 2]), points);
+
+        let image = samples.phase_portrait_image(4, 4, |point| point.x, |point| point.y);
+        assert_eq!(image.dimensions(), (4, 4));
+        let level = |x: u32, y: u32| image.get_pixel(x, y).0[0];
+
+        assert_eq!(level(3, 0), u8::MAX);
+        assert!(level(0, 3) > 0 && level(0, 3) < u8::MAX);
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let top_right = x >= 2 && y < 2;
+            let bottom_left = x < 2 && y >= 2;
+            if !top_right && !bottom_left {
+                assert_eq!(pixel.0[0], 0, "{}, {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_color_averaged_constant_color() {
+        let samples = decay_samples(1.0);
+        let calls = samples.samples[0].color_calls.clone();
+
+        let averaged = samples.color_averaged(4, 5, 0.1);
+        assert_eq!(averaged.to_srgba(), Color::WHITE.to_srgba());
+        assert_eq!(calls.load(std::sync::atomic::Ordering::Relaxed), 5);
+        // The sample itself isn't advanced
+        assert_eq!(samples.samples[4].updates, 0);
+    }
+
+    #[test]
+    fn test_update_with_progress_timeout() {
+        let mut samples = decay_samples(1.0);
+        let mut reported = Vec::new();
+        let finished = samples.update_with_progress(10, 0.1, None, |done| reported.push(done));
+        assert_eq!(finished, 10);
+        assert_eq!(reported, (1..=10).collect::<Vec<_>>());
+
+        let mut samples = decay_samples(1.0);
+        let requested = 1_000_000;
+        let finished =
+            samples.update_with_progress(requested, 0.1, Some(Duration::from_nanos(1)), |_| {});
+        assert!(finished >= 1 && finished < requested, "{}", finished);
+        assert!(samples
+            .samples
+            .iter()
+            .all(|sample| sample.updates == finished));
+    }
+
+    #[test]
+    fn test_forced_time_accumulates() {
+        let mut samples = Samples::from_vec(Dimensions::new(vec![2]), vec![Driven::new(1.0); 2]);
+        samples.update(3, 0.1);
+        samples.update_with_progress(2, 0.25, None, |_| {});
+        samples.update_active(4, 0.05, 0.0);
+
+        let expected = 0.3 + 0.5 + 0.2;
+        for system in &samples.samples {
+            assert!((system.time - expected).abs() < 1e-12, "{}", system.time);
+        }
+    }
+
+    #[test]
+    fn test_update_active_skips_converged() {
+        let mut samples = decay_samples(0.0);
+        assert_eq!(samples.active_count(), 9);
+
+        for _ in 0..5 {
+            samples.update_active(2, 0.1, 1e-9);
+        }
+
+        assert_eq!(samples.active_count(), 0);
+        for (_, system) in samples.iter() {
+            assert_eq!(system.updates, 2);
+        }
+    }
+
+    #[test]
+    fn test_update_active_keeps_evolving() {
+        let mut samples = decay_samples(1.0);
+
+        for _ in 0..5 {
+            samples.update_active(1, 0.1, 1e-9);
+        }
+
+        assert_eq!(samples.active_count(), 9);
+        for (_, system) in samples.iter() {
+            assert_eq!(system.updates, 5);
+        }
+    }
+
+    #[test]
+    fn test_color_cache_skips_converged() {
+        let mut samples = decay_samples(0.0);
+        let color_calls = samples.samples[0].color_calls.clone();
+        samples.enable_color_cache();
+
+        for _ in 0..5 {
+            samples.update_active(1, 0.1, 1e-9);
+            samples.refresh_colors();
+            for index in 0..samples.samples.len() {
+                assert_eq!(samples.sample_color(index), Color::WHITE);
+            }
+        }
+
+        // Converged on the first update, so colored only once after it.
+        assert_eq!(color_calls.load(std::sync::atomic::Ordering::Relaxed), 9);
+
+        samples.update(1, 0.1);
+        samples.refresh_colors();
+        assert_eq!(color_calls.load(std::sync::atomic::Ordering::Relaxed), 18);
+    }
+
+    #[test]
+    fn test_perturb_resets_age() {
+        let mut samples = decay_samples(0.0);
+        // Samples that never changed count as old, even before the first update
+        assert_eq!(samples.age(4), u32::MAX);
+        assert_eq!(samples.fade_in_alpha(4, 2), 1.0);
+        for _ in 0..3 {
+            samples.update_active(1, 0.1, 1e-9);
+        }
+        assert_eq!(samples.age(4), u32::MAX);
+        assert_eq!(samples.active_count(), 0);
+
+        samples.perturb(4, &[1.0]);
+        assert_eq!(samples.age(4), 0);
+        assert_eq!(samples.fade_in_alpha(4, 2), 0.0);
+        assert!(samples.is_active(4));
+
+        samples.update_active(1, 0.1, 1e-9);
+        assert_eq!(samples.age(4), 1);
+        assert_eq!(samples.fade_in_alpha(4, 2), 0.5);
+        assert_eq!(samples.age(0), u32::MAX);
+        assert_eq!(samples.fade_in_alpha(0, 2), 1.0);
+        assert_eq!(samples.fade_in_alpha(4, 0), 1.0);
+    }
+
+    #[test]
+    fn test_resumed_samples_restart_age() {
+        let mut samples = decay_samples(0.0);
+        samples.perturb(4, &[1.0]);
+        samples.update_active(1, 0.1, 1e-9);
+        assert_eq!(samples.active_count(), 1);
+
+        // Updating without freezing resumes the frozen samples, the evolving one keeps its age
+        samples.update(1, 0.1);
+        assert_eq!(samples.active_count(), 9);
+        assert_eq!(samples.age(4), 2);
+        assert_eq!(samples.age(0), 1);
+        assert_eq!(samples.fade_in_alpha(0, 4), 0.25);
+    }
+
+    #[test]
+    fn test_velocity_image() {
+        let mut samples = decay_samples(0.0);
+        samples.perturb(4, &[10.0]);
+        samples.update(1, 0.1);
+        assert!(samples.velocity_image().samples.iter().all(|&v| v == 0.0));
+
+        samples.enable_history();
+        samples.update(1, 0.1);
+        let velocity = samples.velocity_image();
+        assert_eq!(velocity.samples[4], 1.0);
+        for (index, &value) in velocity.samples.iter().enumerate() {
+            if index != 4 {
+                assert_eq!(value, 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_origin() {
+        let dimensions = Dimensions::new(vec![5, 4]);
+        let cases = [
+            (GridOrigin::Corner, vec![0, 0]),
+            (GridOrigin::Center, vec![2, 2]),
+            (GridOrigin::Custom(vec![4, 1]), vec![4, 1]),
+            (GridOrigin::Custom(vec![9]), vec![4, 2]),
+        ];
+
+        for (origin, expected) in cases {
+            assert_eq!(origin.cell(&dimensions), expected);
+
+            let samples = Samples::new_with_origin(
+                Mandelbrot::new(MandelbrotColorSchema::Distance),
+                dimensions.clone(),
+                &[1.0, 1.0],
+                &[],
+                &[],
+                0.5,
+                &origin,
+            );
+            for (pos, system) in samples.iter() {
+                let offset = DVec2::new(
+                    pos[0] as f64 - expected[0] as f64,
+                    pos[1] as f64 - expected[1] as f64,
+                );
+                assert_eq!(system.c, offset * 0.5, "{:?} at {:?}", origin, pos);
+            }
+        }
+    }
+
+    #[test]
+    fn test_new_is_cartesian_sweep() {
+        let mut base = Mandelbrot::new(MandelbrotColorSchema::Distance);
+        base.c = DVec2::new(-0.5, 0.25);
+        let new = |sizes: Vec<usize>, scales: &[f64]| {
+            Samples::new(base.clone(), Dimensions::new(sizes), scales, 0.1)
+        };
+
+        let samples = new(vec![5, 4], &[1.0, 3.0]);
+        for (pos, system) in samples.iter() {
+            let expected =
+                base.c + DVec2::new(pos[0] as f64 - 2.0, (pos[1] as f64 - 2.0) * 3.0) * 0.1;
+            assert!((system.c - expected).length() < 1e-12, "{:?}", pos);
+        }
+
+        // Swapping the axes visits the cells in a different order, with the same result
+        let transposed = new(vec![4, 5], &[3.0, 1.0]);
+        for (pos, system) in transposed.iter() {
+            let index = samples.dimensions.pos_to_index(&[pos[1], pos[0]]);
+            let swapped = DVec2::new(system.c.y, system.c.x) - DVec2::new(base.c.y, base.c.x);
+            let offset = samples.samples[index].c - base.c;
+            assert!((swapped - offset).length() < 1e-12, "{:?}", pos);
+        }
+    }
+
+    #[test]
+    fn test_independent_mutations() {
+        let dimensions = Dimensions::new(vec![3, 2]);
+        let samples = Samples::new(
+            Mandelbrot::new(MandelbrotColorSchema::Distance),
+            dimensions.clone(),
+            &[1.0, 2.0],
+            0.5,
+        );
+
+        // Every cell only carries its own mutation relative to the center `[1, 1]`, nothing adds
+        // up along the grid
+        for (pos, system) in samples.iter() {
+            let own = DVec2::new(pos[0] as f64 - 1.0, (pos[1] as f64 - 1.0) * 2.0) * 0.5;
+            assert_eq!(system.c, own, "{:?}", pos);
+        }
+    }
+
+    #[test]
+    fn test_offsets_shift_samples() {
+        let dimensions = Dimensions::new(vec![4, 3]);
+        let new = |offsets: &[f64]| {
+            Samples::new_with_origin(
+                Mandelbrot::new(MandelbrotColorSchema::Distance),
+                dimensions.clone(),
+                &[1.0, 2.0],
+                offsets,
+                &[],
+                0.1,
+                &GridOrigin::Center,
+            )
+        };
+
+        let plain = new(&[]);
+        let shifted = new(&[0.25, -3.0]);
+        let partial = new(&[0.25]);
+        for index in 0..dimensions.volume() {
+            let c = plain.samples[index].c;
+            assert_eq!(shifted.samples[index].c, c + DVec2::new(0.25, -3.0));
+            assert_eq!(partial.samples[index].c, c + DVec2::new(0.25, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_empty_dimensions_clamped() {
+        let mut samples = Samples::new(
+            Mandelbrot::new(MandelbrotColorSchema::Distance),
+            Dimensions::new(vec![0, 3]),
+            &[1.0, 1.0],
+            0.1,
+        );
+        assert_eq!(samples.dimensions.sizes(), &[1, 3]);
+        assert_eq!(samples.samples.len(), 3);
+        samples.update(1, 0.1);
+
+        let single = Samples::new(Decay::new(1.0), Dimensions::new(vec![0, 0]), &[1.0], 1.0);
+        assert_eq!(single.samples.len(), 1);
+    }
+
+    #[test]
+    fn test_negative_direction_mirrors_axis() {
+        let dimensions = Dimensions::new(vec![5, 4]);
+        let new = |directions: &[AxisDirection]| {
+            Samples::new_with_origin(
+                Mandelbrot::new(MandelbrotColorSchema::Distance),
+                dimensions.clone(),
+                &[1.0, 2.0],
+                &[0.5, -1.0],
+                directions,
+                0.1,
+                &GridOrigin::Center,
+            )
+        };
+
+        let plain = new(&[]);
+        let flipped = new(&[AxisDirection::Negative]);
+        for (pos, system) in flipped.iter() {
+            // Mirrored around the origin column `2`
+            let mirrored = plain.samples[dimensions.pos_to_index(&[4 - pos[0], pos[1]])].c;
+            let same = plain.samples[dimensions.pos_to_index(&pos)].c;
+            assert!((system.c.x - mirrored.x).abs() < 1e-12, "{:?}", pos);
+            assert_eq!(system.c.y, same.y);
+        }
+        assert!(flipped.samples[0].c.x > flipped.samples[4].c.x);
+    }
+
+    #[test]
+    fn test_map() {
+        let dimensions = Dimensions::new(vec![2, 3]);
+        let samples = Samples::from_vec(
+            dimensions.clone(),
+            (0..dimensions.volume() as i32).collect(),
+        );
+
+        let mapped = samples.map(|&value| value as f64 * 0.5);
+
+        assert_eq!(mapped.dimensions.sizes(), samples.dimensions.sizes());
+        for ((pos, &value), (mapped_pos, &mapped_value)) in samples.iter().zip(mapped.iter()) {
+            assert_eq!(pos, mapped_pos);
+            assert_eq!(value as f64 * 0.5, mapped_value);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_iter() {
+        let dimensions = Dimensions::new(vec![5, 3, 4]);
+        let samples = Samples::from_vec(dimensions.clone(), (0..dimensions.volume()).collect());
+
+        let mut visited = samples
+            .par_iter()
+            .map(|(pos, &index)| (index, pos))
+            .collect::<Vec<_>>();
+        visited.sort();
+
+        assert_eq!(visited.len(), dimensions.volume());
+        for (expected, (index, pos)) in visited.into_iter().enumerate() {
+            assert_eq!(index, expected);
+            assert_eq!(pos, dimensions.index_to_pos(index));
+        }
+    }
+
+    #[test]
+    fn test_edge_image() {
+        let dimensions = Dimensions::new(vec![6, 4]);
+        let samples = Samples::from_vec(
+            dimensions.clone(),
+            dimensions.iter().map(|pos| pos[0]).collect(),
+        );
+
+        // Sharp step between `x = 2` and `x = 3`
+        let edges = samples.edge_image(|&x| if x < 3 { 0.0 } else { 1.0 });
+        assert_eq!(edges.dimensions.sizes(), samples.dimensions.sizes());
+
+        for (pos, &magnitude) in edges.iter() {
+            match pos[0] {
+                2 | 3 => assert!(magnitude > 0.9, "{:?}: {}", pos, magnitude),
+                _ => assert_eq!(magnitude, 0.0, "{:?}", pos),
+            }
+        }
+    }
+
+    #[test]
+    fn test_slice_2d() {
+        let dimensions = Dimensions::new(vec![3, 4, 5]);
+        let samples = Samples::from_vec(dimensions.clone(), dimensions.iter().collect());
+
+        let plane = samples.slice_2d(2, 0, &[0, 3, 0]);
+        assert_eq!(plane.dimensions.sizes(), &[5, 3]);
+
+        for (pos, value) in plane.iter() {
+            let expected = &samples.samples[samples.dimensions.pos_to_index(&[pos[1], 3, pos[0]])];
+            assert_eq!(value, expected);
+            assert_eq!(value, &vec![pos[1], 3, pos[0]]);
+        }
+    }
 }
@@ -0,0 +1,143 @@
+use crate::*;
+use bevy::color::Color;
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
+
+/// Arnold's cat map, `(x, y) -> (2x + y, x + y) mod 1`: an area-preserving map of the unit torus
+/// onto itself. Every point with rational coordinates is periodic, all others wander chaotically.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArnoldCat {
+    /// Starting point, restored by [`ChaoticSystem::reset_state`].
+    pub initial: DVec2,
+    pub position: DVec2,
+}
+
+impl ArnoldCat {
+    pub fn new(initial: DVec2) -> Self {
+        let initial = wrap(initial);
+        ArnoldCat {
+            initial,
+            position: initial,
+        }
+    }
+}
+
+fn wrap(position: DVec2) -> DVec2 {
+    position.rem_euclid(DVec2::ONE)
+}
+
+impl ChaoticSystem for ArnoldCat {
+    fn name() -> &'static str {
+        "Arnold's cat"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["x".to_string(), "y".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        let offset = DVec2::new(
+            pos.first().copied().unwrap_or_default(),
+            pos.get(1).copied().unwrap_or_default(),
+        );
+        self.initial = wrap(self.initial + offset);
+        self.position = wrap(self.position + offset);
+    }
+
+    fn reset_state(&mut self) {
+        self.position = self.initial;
+    }
+
+    fn update(&mut self, _dt: f64) {
+        let DVec2 { x, y } = self.position;
+
+        self.position = wrap(DVec2::new(2.0 * x + y, x + y));
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        ArnoldCat {
+            initial: self.initial.lerp(other.initial, t),
+            position: self.position.lerp(other.position, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        position_to_color(self.position, DVec2::splat(0.5), 0.5)
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        toroidal_distance(self.position, other.position, DVec2::ONE)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.position.x, self.position.y]
+    }
+
+    fn spatial_bounds(&self) -> Option<(DVec2, DVec2)> {
+        Some((DVec2::ZERO, DVec2::ONE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Points of the `n` by `n` grid, `n` a power of two so the map is exact in floating point.
+    fn grid(n: usize) -> Vec<ArnoldCat> {
+        (0..n * n)
+            .map(|i| {
+                let cell = DVec2::new((i % n) as f64, (i / n) as f64);
+                ArnoldCat::new(cell / n as f64)
+            })
+            .collect()
+    }
+
+    fn cells(points: &[ArnoldCat], n: usize) -> HashSet<(i64, i64)> {
+        points
+            .iter()
+            .map(|point| {
+                let cell = point.position * n as f64;
+                (cell.x as i64, cell.y as i64)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_preserves_grid_count() {
+        let n = 16;
+        let mut points = grid(n);
+        for _ in 0..10 {
+            points.iter_mut().for_each(|point| point.update(1.0));
+            // The map permutes the grid: no two points merge, none is lost
+            assert_eq!(cells(&points, n).len(), n * n);
+        }
+    }
+
+    #[test]
+    fn test_periodic_on_rational_grid() {
+        // The period of the cat map on the 2^k grid is 3 * 2^(k - 2)
+        let n = 16;
+        let mut points = grid(n);
+        for step in 1..=24 {
+            points.iter_mut().for_each(|point| point.update(1.0));
+            let returned = points.iter().all(|point| point.position == point.initial);
+            assert_eq!(returned, step % 12 == 0, "step {}", step);
+        }
+    }
+
+    #[test]
+    fn test_distance_wraps() {
+        let a = ArnoldCat::new(DVec2::new(0.01, 0.5));
+        let b = ArnoldCat::new(DVec2::new(0.99, 0.5));
+        assert!((a.distance(&b) - 0.02).abs() < 1e-12);
+    }
+}
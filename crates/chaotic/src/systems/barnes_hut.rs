@@ -0,0 +1,158 @@
+use super::{gravity, Body};
+use bevy::math::DVec2;
+
+/// Cells smaller than this stop splitting and keep all their bodies, so coincident bodies can't
+/// recurse forever.
+const MIN_HALF_SIZE: f64 = 1e-9;
+
+/// Barnes-Hut quadtree over body positions, used by [`super::NBody::update_barnes_hut`].
+pub(super) struct QuadTree {
+    nodes: Vec<Node>,
+}
+
+struct Node {
+    center: DVec2,
+    half_size: f64,
+    mass: f64,
+    /// Mass-weighted sum of positions, divided by `mass` once the tree is built.
+    center_of_mass: DVec2,
+    /// Bodies of a leaf.
+    bodies: Vec<usize>,
+    /// Index of the first of four consecutive children, `None` for leaves.
+    children: Option<usize>,
+}
+
+impl Node {
+    fn new(center: DVec2, half_size: f64) -> Self {
+        Node {
+            center,
+            half_size,
+            mass: 0.0,
+            center_of_mass: DVec2::ZERO,
+            bodies: Vec::new(),
+            children: None,
+        }
+    }
+
+    fn quadrant(&self, position: DVec2) -> usize {
+        (position.x >= self.center.x) as usize + 2 * (position.y >= self.center.y) as usize
+    }
+
+    fn contains(&self, position: DVec2) -> bool {
+        let offset = (position - self.center).abs();
+        offset.x <= self.half_size && offset.y <= self.half_size
+    }
+}
+
+impl QuadTree {
+    pub(super) fn new(bodies: &[Body]) -> Self {
+        let (min, max) = bodies.iter().fold(
+            (DVec2::splat(f64::INFINITY), DVec2::splat(f64::NEG_INFINITY)),
+            |(min, max), body| (min.min(body.position), max.max(body.position)),
+        );
+        let center = if bodies.is_empty() {
+            DVec2::ZERO
+        } else {
+            (min + max) / 2.0
+        };
+        let half_size = ((max - min).max_element() / 2.0).max(MIN_HALF_SIZE);
+
+        let mut tree = QuadTree {
+            nodes: vec![Node::new(center, half_size)],
+        };
+        for i in 0..bodies.len() {
+            tree.insert(0, i, bodies);
+        }
+        for node in &mut tree.nodes {
+            if node.mass > 0.0 {
+                node.center_of_mass /= node.mass;
+            }
+        }
+        tree
+    }
+
+    fn insert(&mut self, mut node: usize, i: usize, bodies: &[Body]) {
+        let body = &bodies[i];
+        loop {
+            self.nodes[node].mass += body.mass;
+            self.nodes[node].center_of_mass += body.position * body.mass;
+
+            if let Some(first) = self.nodes[node].children {
+                node = first + self.nodes[node].quadrant(body.position);
+                continue;
+            }
+
+            if self.nodes[node].bodies.is_empty() || self.nodes[node].half_size < MIN_HALF_SIZE {
+                self.nodes[node].bodies.push(i);
+                return;
+            }
+
+            self.split(node, bodies);
+            node = self.nodes[node].children.unwrap() + self.nodes[node].quadrant(body.position);
+        }
+    }
+
+    /// Turns a leaf into four children, moving its bodies into them.
+    fn split(&mut self, node: usize, bodies: &[Body]) {
+        let first = self.nodes.len();
+        let half_size = self.nodes[node].half_size / 2.0;
+        let center = self.nodes[node].center;
+        for quadrant in 0..4 {
+            let sign = DVec2::new(
+                if quadrant & 1 == 0 { -1.0 } else { 1.0 },
+                if quadrant & 2 == 0 { -1.0 } else { 1.0 },
+            );
+            self.nodes
+                .push(Node::new(center + sign * half_size, half_size));
+        }
+        self.nodes[node].children = Some(first);
+
+        for j in std::mem::take(&mut self.nodes[node].bodies) {
+            let child = first + self.nodes[node].quadrant(bodies[j].position);
+            let child = &mut self.nodes[child];
+            child.mass += bodies[j].mass;
+            child.center_of_mass += bodies[j].position * bodies[j].mass;
+            child.bodies.push(j);
+        }
+    }
+
+    /// Gravity acting on the body `i`. Cells seen under an angle smaller than `theta` (cell size
+    /// over distance) are replaced by their center of mass.
+    pub(super) fn force(
+        &self,
+        i: usize,
+        bodies: &[Body],
+        g: f64,
+        theta: f64,
+        softening: f64,
+    ) -> DVec2 {
+        let body_i = &bodies[i];
+        let pull = |position: DVec2, mass: f64| {
+            gravity(position - body_i.position, g, mass, softening) * body_i.mass
+        };
+
+        let mut force = DVec2::ZERO;
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if node.mass == 0.0 {
+                continue;
+            }
+
+            let Some(first) = node.children else {
+                for &j in node.bodies.iter().filter(|&&j| j != i) {
+                    force += pull(bodies[j].position, bodies[j].mass);
+                }
+                continue;
+            };
+
+            let distance = node.center_of_mass.distance(body_i.position);
+            if !node.contains(body_i.position) && 2.0 * node.half_size < theta * distance {
+                force += pull(node.center_of_mass, node.mass);
+            } else {
+                stack.extend(first..first + 4);
+            }
+        }
+        force
+    }
+}
@@ -0,0 +1,103 @@
+use crate::*;
+use bevy::color::Color;
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
+
+const INITIAL_POSITION: DVec2 = DVec2::new(0.3, 0.0);
+
+/// Bogdanov map, a discretization of the Bogdanov-Takens bifurcation. Area-preserving for
+/// `epsilon = mu = 0`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bogdanov {
+    pub epsilon: f64,
+    pub k: f64,
+    pub mu: f64,
+    pub position: DVec2,
+}
+
+impl Bogdanov {
+    pub fn new(epsilon: f64, k: f64, mu: f64) -> Self {
+        Bogdanov {
+            epsilon,
+            k,
+            mu,
+            position: INITIAL_POSITION,
+        }
+    }
+
+    /// Parameters of the well-known chaotic orbits.
+    pub fn canonical() -> Self {
+        Self::new(0.0, 1.2, 0.0)
+    }
+}
+
+impl ChaoticSystem for Bogdanov {
+    fn name() -> &'static str {
+        "Bogdanov"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["epsilon".to_string(), "mu".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.epsilon += pos.first().copied().unwrap_or_default();
+        self.mu += pos.get(1).copied().unwrap_or_default();
+    }
+
+    fn reset_state(&mut self) {
+        self.position = INITIAL_POSITION;
+    }
+
+    fn update(&mut self, _dt: f64) {
+        let DVec2 { x, y } = self.position;
+
+        let y = y + self.epsilon * y + self.k * x * (x - 1.0) + self.mu * x * y;
+        self.position = DVec2::new(x + y, y);
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Bogdanov {
+            epsilon: lerp_f64(self.epsilon, other.epsilon, t),
+            k: lerp_f64(self.k, other.k, t),
+            mu: lerp_f64(self.mu, other.mu, t),
+            position: self.position.lerp(other.position, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        position_to_color(self.position, DVec2::ZERO, 0.5)
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.position.distance(other.position)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.position.x, self.position.y]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_bounded() {
+        let mut system = Bogdanov::canonical();
+
+        for _ in 0..5000 {
+            system.update(1.0);
+            assert!(system.position.is_finite());
+            assert!(system.position.length() < 1.0, "{:?}", system.position);
+        }
+    }
+}
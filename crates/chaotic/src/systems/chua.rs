@@ -0,0 +1,146 @@
+use crate::*;
+use bevy::color::{Color, Hsva};
+use bevy::math::DVec3;
+use serde::{Deserialize, Serialize};
+
+const INITIAL_POSITION: DVec3 = DVec3::new(0.7, 0.0, 0.0);
+
+/// Chua's circuit: two capacitors and an inductor around a nonlinear resistor (Chua's diode)
+/// with a piecewise-linear current, slope `m0` inside `|x| < 1` and `m1` outside. The canonical
+/// parameters give the "double scroll" attractor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChuaCircuit {
+    pub alpha: f64,
+    pub beta: f64,
+    pub m0: f64,
+    pub m1: f64,
+    pub position: DVec3,
+}
+
+impl ChuaCircuit {
+    pub fn new(alpha: f64, beta: f64, m0: f64, m1: f64) -> Self {
+        ChuaCircuit {
+            alpha,
+            beta,
+            m0,
+            m1,
+            position: INITIAL_POSITION,
+        }
+    }
+
+    /// Parameters of the double scroll attractor.
+    pub fn double_scroll() -> Self {
+        Self::new(15.6, 28.0, -8.0 / 7.0, -5.0 / 7.0)
+    }
+
+    /// Current through Chua's diode at voltage `x`.
+    pub fn diode(&self, x: f64) -> f64 {
+        self.m1 * x + 0.5 * (self.m0 - self.m1) * ((x + 1.0).abs() - (x - 1.0).abs())
+    }
+
+    fn derivative(&self, p: DVec3) -> DVec3 {
+        DVec3::new(
+            self.alpha * (p.y - p.x - self.diode(p.x)),
+            p.x - p.y + p.z,
+            -self.beta * p.y,
+        )
+    }
+}
+
+impl ChaoticSystem for ChuaCircuit {
+    fn name() -> &'static str {
+        "Chua's circuit"
+    }
+
+    fn suggested_dt() -> f64 {
+        0.01
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["alpha".to_string(), "beta".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.alpha += pos.first().copied().unwrap_or_default();
+        self.beta += pos.get(1).copied().unwrap_or_default();
+    }
+
+    fn reset_state(&mut self) {
+        self.position = INITIAL_POSITION;
+    }
+
+    fn update(&mut self, dt: f64) {
+        self.position = rk4_step(self.position, dt, |p| self.derivative(p));
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        ChuaCircuit {
+            alpha: lerp_f64(self.alpha, other.alpha, t),
+            beta: lerp_f64(self.beta, other.beta, t),
+            m0: lerp_f64(self.m0, other.m0, t),
+            m1: lerp_f64(self.m1, other.m1, t),
+            position: self.position.lerp(other.position, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        let DVec3 { x, y, z } = self.position;
+        if !self.position.is_finite() {
+            return Color::BLACK;
+        }
+
+        // The scrolls wind around `x = ±1.5` in the `x`/`z` plane
+        let hue = normalize_angle(z.atan2(x.abs() - 1.5));
+        let saturation = if x < 0.0 { 0.6 } else { 0.9 };
+        let value = (y.abs() * 2.0).clamp(0.0, 1.0) * 0.6 + 0.4;
+
+        Hsva::new((hue * 360.0) as f32, saturation, value as f32, 1.0).into()
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.position.distance(other.position)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        self.position.to_array().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diode_slopes() {
+        let system = ChuaCircuit::double_scroll();
+        assert!((system.diode(0.5) - 0.5 * system.m0).abs() < 1e-12);
+        assert!((system.diode(-3.0) - (-system.m0 - 2.0 * system.m1)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_double_scroll_chaotic() {
+        let mut system = ChuaCircuit::double_scroll();
+        let dt = 0.01;
+
+        let (mut left, mut right) = (false, false);
+        for _ in 0..50000 {
+            system.update(dt);
+            let DVec3 { x, y, z } = system.position;
+            assert!(
+                x.abs() < 4.0 && y.abs() < 1.0 && z.abs() < 6.0,
+                "{}",
+                system.position
+            );
+            left |= x < -1.0;
+            right |= x > 1.0;
+        }
+
+        // Both scrolls are visited
+        assert!(left && right);
+        assert!(largest_lyapunov(&system, 20000, dt, 1e-8) > 0.0);
+    }
+}
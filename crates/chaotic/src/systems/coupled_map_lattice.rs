@@ -0,0 +1,178 @@
+use crate::*;
+use bevy::color::{Color, Hsva};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum CoupledMapColorSchema {
+    /// Hue from the mean of all cells.
+    Mean,
+    /// Hue from a single cell.
+    Cell(usize),
+}
+
+/// Ring of [`LogisticMap`] cells with diffusive nearest-neighbour coupling:
+/// `x_i' = (1 - ε) f(x_i) + ε/2 (f(x_{i-1}) + f(x_{i+1}))` with `f(x) = r x (1 - x)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoupledMapLattice {
+    pub r: f64,
+    pub epsilon: f64,
+    pub cells: Vec<f64>,
+    pub color_schema: CoupledMapColorSchema,
+}
+
+impl CoupledMapLattice {
+    pub fn new(size: usize, r: f64, epsilon: f64, color_schema: CoupledMapColorSchema) -> Self {
+        CoupledMapLattice {
+            r,
+            epsilon,
+            cells: Self::initial_cells(size),
+            color_schema,
+        }
+    }
+
+    /// Slightly different starting values, so the cells don't move in lockstep.
+    fn initial_cells(size: usize) -> Vec<f64> {
+        (0..size).map(|i| 0.5 + 0.1 * (i as f64).sin()).collect()
+    }
+
+    fn local_map(&self, x: f64) -> f64 {
+        self.r * x * (1.0 - x)
+    }
+
+    fn color_value(&self) -> f64 {
+        match self.color_schema {
+            CoupledMapColorSchema::Mean => {
+                self.cells.iter().sum::<f64>() / self.cells.len().max(1) as f64
+            }
+            CoupledMapColorSchema::Cell(i) => self.cells.get(i).copied().unwrap_or_default(),
+        }
+    }
+}
+
+impl ChaoticSystem for CoupledMapLattice {
+    fn name() -> &'static str {
+        "Coupled map lattice"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["r".to_string(), "epsilon".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        let mut pos = pos.iter().copied();
+        self.r += pos.next().unwrap_or_default();
+        self.epsilon += pos.next().unwrap_or_default();
+    }
+
+    fn reset_state(&mut self) {
+        self.cells = Self::initial_cells(self.cells.len());
+    }
+
+    fn update(&mut self, _dt: f64) {
+        let mapped = self
+            .cells
+            .iter()
+            .map(|&x| self.local_map(x))
+            .collect::<Vec<_>>();
+        let len = mapped.len();
+        for (i, cell) in self.cells.iter_mut().enumerate() {
+            let left = mapped[(i + len - 1) % len];
+            let right = mapped[(i + 1) % len];
+            *cell = (1.0 - self.epsilon) * mapped[i] + self.epsilon / 2.0 * (left + right);
+        }
+    }
+
+    /// Cells missing in `other` are kept as they are in `self`.
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        let mut cells = self
+            .cells
+            .iter()
+            .zip(&other.cells)
+            .map(|(&a, &b)| lerp_f64(a, b, t))
+            .collect::<Vec<_>>();
+        cells.extend_from_slice(&self.cells[cells.len()..]);
+
+        CoupledMapLattice {
+            r: lerp_f64(self.r, other.r, t),
+            epsilon: lerp_f64(self.epsilon, other.epsilon, t),
+            cells,
+            color_schema: self.color_schema,
+        }
+    }
+
+    fn color(&self) -> Color {
+        let x = self.color_value();
+        if !x.is_finite() {
+            return Color::BLACK;
+        }
+        let x = x.clamp(0.0, 1.0) as f32;
+        Hsva::new(x * 300.0, 0.8, 0.3 + 0.7 * x, 1.0).into()
+    }
+
+    /// Root mean square difference of the cells both lattices have.
+    fn distance(&self, other: &Self) -> f64 {
+        let count = self.cells.len().min(other.cells.len());
+        if count == 0 {
+            return 0.0;
+        }
+        let sum_sq = self
+            .cells
+            .iter()
+            .zip(&other.cells)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f64>();
+        (sum_sq / count as f64).sqrt()
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        self.cells.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uncoupled_cells_are_logistic_maps() {
+        let r = 3.9;
+        let mut lattice = CoupledMapLattice::new(8, r, 0.0, CoupledMapColorSchema::Mean);
+        let mut maps = lattice
+            .cells
+            .iter()
+            .map(|&x| LogisticMap { r, x })
+            .collect::<Vec<_>>();
+
+        for _ in 0..100 {
+            lattice.update(1.0);
+            for map in &mut maps {
+                map.update(1.0);
+            }
+        }
+
+        for (cell, map) in lattice.cells.iter().zip(&maps) {
+            assert_eq!(*cell, map.x);
+        }
+    }
+
+    #[test]
+    fn test_coupling_is_local() {
+        let mut lattice = CoupledMapLattice::new(8, 3.9, 1.0 / 3.0, CoupledMapColorSchema::Cell(0));
+        lattice.cells = vec![0.3; 8];
+        lattice.cells[0] = 0.3 + 1e-12;
+        lattice.update(1.0);
+
+        // Diffusion spreads a perturbation of one cell to its neighbours only
+        assert_ne!(lattice.cells[1], lattice.cells[2]);
+        assert_eq!(lattice.cells[2], lattice.cells[6]);
+        assert_eq!(lattice.cells[1], lattice.cells[7]);
+    }
+}
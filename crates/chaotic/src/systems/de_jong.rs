@@ -0,0 +1,114 @@
+use crate::*;
+use bevy::color::Color;
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
+
+const INITIAL_POSITION: DVec2 = DVec2::new(0.1, 0.1);
+
+/// Peter de Jong attractor. Both coordinates are differences of a sine and a cosine, so the orbit
+/// never leaves `[-2, 2]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeJong {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub position: DVec2,
+}
+
+impl DeJong {
+    pub fn new(a: f64, b: f64, c: f64, d: f64) -> Self {
+        DeJong {
+            a,
+            b,
+            c,
+            d,
+            position: INITIAL_POSITION,
+        }
+    }
+
+    /// Parameters of the well-known chaotic attractor.
+    pub fn canonical() -> Self {
+        Self::new(1.4, -2.3, 2.4, -2.1)
+    }
+}
+
+impl ChaoticSystem for DeJong {
+    fn name() -> &'static str {
+        "de Jong"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["a".to_string(), "b".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.a += pos.first().copied().unwrap_or_default();
+        self.b += pos.get(1).copied().unwrap_or_default();
+    }
+
+    fn reset_state(&mut self) {
+        self.position = INITIAL_POSITION;
+    }
+
+    fn update(&mut self, _dt: f64) {
+        let DVec2 { x, y } = self.position;
+
+        self.position = DVec2::new(
+            (self.a * y).sin() - (self.b * x).cos(),
+            (self.c * x).sin() - (self.d * y).cos(),
+        );
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        DeJong {
+            a: lerp_f64(self.a, other.a, t),
+            b: lerp_f64(self.b, other.b, t),
+            c: lerp_f64(self.c, other.c, t),
+            d: lerp_f64(self.d, other.d, t),
+            position: self.position.lerp(other.position, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        position_to_color(self.position, DVec2::ZERO, 2.0)
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.position.distance(other.position)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.position.x, self.position.y]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_for_any_parameters() {
+        let systems = [
+            DeJong::canonical(),
+            DeJong::new(-2.0, -2.0, -1.2, 2.0),
+            DeJong::new(2.01, -2.53, 1.61, -0.33),
+            DeJong::new(100.0, 0.0, -37.5, 1e-3),
+        ];
+
+        for mut system in systems {
+            for _ in 0..2000 {
+                system.update(1.0);
+                assert!(system.position.abs().max_element() <= 2.0, "{:?}", system);
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use crate::*;
 use bevy::color::{Color, Hsva};
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DoublePendulum {
     pub length1: f64,
     pub length2: f64,
@@ -39,6 +41,22 @@ impl DoublePendulum {
         self
     }
 
+    /// Position of the first bob relative to the pivot. Angles are measured from the downward
+    /// vertical, Y points up.
+    pub fn bob1_position(&self) -> DVec2 {
+        DVec2::new(self.angle1.sin(), -self.angle1.cos()) * self.length1
+    }
+
+    /// Position of the second bob relative to the pivot, see [`Self::bob1_position`].
+    pub fn bob2_position(&self) -> DVec2 {
+        self.bob1_position() + DVec2::new(self.angle2.sin(), -self.angle2.cos()) * self.length2
+    }
+
+    /// Reach of the fully extended pendulum.
+    pub fn total_length(&self) -> f64 {
+        self.length1 + self.length2
+    }
+
     pub fn update(&mut self, gravity: f64) {
         let num = -gravity * (2.0 * self.mass1 + self.mass2) * self.angle1.sin()
             - self.mass2 * gravity * (self.angle1 - 2.0 * self.angle2).sin()
@@ -90,3 +108,23 @@ impl DoublePendulum {
         .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bob_positions() {
+        let pendulum = DoublePendulum::new(2.0, 1.0, 1.0, 1.0);
+        assert_eq!(pendulum.bob1_position(), DVec2::new(0.0, -2.0));
+        assert_eq!(pendulum.bob2_position(), DVec2::new(0.0, -3.0));
+        assert_eq!(pendulum.total_length(), 3.0);
+
+        let pendulum = pendulum
+            .with_angle1(std::f64::consts::FRAC_PI_2)
+            .with_angle2(std::f64::consts::PI);
+        assert!(pendulum.bob1_position().distance(DVec2::new(2.0, 0.0)) < 1e-12);
+        assert!(pendulum.bob2_position().distance(DVec2::new(2.0, 1.0)) < 1e-12);
+        assert!(pendulum.bob2_position().length() <= pendulum.total_length());
+    }
+}
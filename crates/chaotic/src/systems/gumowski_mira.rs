@@ -0,0 +1,101 @@
+use crate::*;
+use bevy::color::Color;
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
+
+const INITIAL_POSITION: DVec2 = DVec2::new(0.5, 0.0);
+
+/// Gumowski-Mira map. `b = 1` makes the map area-preserving.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GumowskiMira {
+    pub a: f64,
+    pub b: f64,
+    pub position: DVec2,
+}
+
+impl GumowskiMira {
+    pub fn new(a: f64, b: f64) -> Self {
+        GumowskiMira {
+            a,
+            b,
+            position: INITIAL_POSITION,
+        }
+    }
+
+    fn f(&self, x: f64) -> f64 {
+        self.a * x + 2.0 * (1.0 - self.a) * x * x / (1.0 + x * x)
+    }
+}
+
+impl ChaoticSystem for GumowskiMira {
+    fn name() -> &'static str {
+        "Gumowski-Mira"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["a".to_string(), "b".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.a += pos.first().copied().unwrap_or_default();
+        self.b += pos.get(1).copied().unwrap_or_default();
+    }
+
+    fn reset_state(&mut self) {
+        self.position = INITIAL_POSITION;
+    }
+
+    fn update(&mut self, _dt: f64) {
+        let DVec2 { x, y } = self.position;
+        let new_x = self.b * y + self.f(x);
+        let new_y = -x + self.f(new_x);
+
+        self.position = DVec2::new(new_x, new_y);
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        GumowskiMira {
+            a: lerp_f64(self.a, other.a, t),
+            b: lerp_f64(self.b, other.b, t),
+            position: self.position.lerp(other.position, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        position_to_color(self.position, DVec2::ZERO, 10.0)
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.position.distance(other.position)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.position.x, self.position.y]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closed_attractor_bounded() {
+        for (a, b, limit) in [(0.3, 1.0, 10.0), (-0.8, 1.0, 100.0)] {
+            let mut system = GumowskiMira::new(a, b);
+
+            for _ in 0..5000 {
+                system.update(1.0);
+                assert!(system.position.is_finite());
+                assert!(system.position.length() < limit, "{:?}", system.position);
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::*;
+use bevy::color::Color;
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
+
+/// Ikeda map, a model of light going around a nonlinear optical resonator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ikeda {
+    pub u: f64,
+    /// Starting point, restored by [`ChaoticSystem::reset_state`].
+    #[serde(default)]
+    pub initial: DVec2,
+    pub position: DVec2,
+}
+
+impl Ikeda {
+    pub fn new(u: f64) -> Self {
+        Ikeda {
+            u,
+            initial: DVec2::ZERO,
+            position: DVec2::ZERO,
+        }
+    }
+}
+
+impl ChaoticSystem for Ikeda {
+    fn name() -> &'static str {
+        "Ikeda"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        3
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["x".to_string(), "y".to_string(), "u".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        let offset = DVec2::new(
+            pos.first().copied().unwrap_or_default(),
+            pos.get(1).copied().unwrap_or_default(),
+        );
+        self.initial += offset;
+        self.position += offset;
+        self.u += pos.get(2).copied().unwrap_or_default();
+    }
+
+    fn reset_state(&mut self) {
+        self.position = self.initial;
+    }
+
+    fn update(&mut self, _dt: f64) {
+        let DVec2 { x, y } = self.position;
+        let t = 0.4 - 6.0 / (1.0 + x * x + y * y);
+        let (sin_t, cos_t) = t.sin_cos();
+
+        self.position = DVec2::new(
+            1.0 + self.u * (x * cos_t - y * sin_t),
+            self.u * (x * sin_t + y * cos_t),
+        );
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Ikeda {
+            u: lerp_f64(self.u, other.u, t),
+            initial: self.initial.lerp(other.initial, t),
+            position: self.position.lerp(other.position, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        position_to_color(self.position, DVec2::new(0.5, -0.5), 1.0)
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.position.distance(other.position)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.position.x, self.position.y]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_attractor() {
+        let mut system = Ikeda::new(0.9);
+        system.mutate(&[0.1, 0.1]);
+
+        for _ in 0..5000 {
+            system.update(1.0);
+            assert!(system.position.is_finite());
+            assert!(system.position.length() < 20.0, "{:?}", system.position);
+        }
+
+        // Still wandering over the attractor rather than sitting at a fixed point
+        let prev = system.clone();
+        system.update(1.0);
+        assert!(system.distance(&prev) > 1e-3);
+    }
+
+    #[test]
+    fn test_reset_state() {
+        let mut system = Ikeda::new(0.9);
+        system.mutate(&[0.25, -0.5, 0.05]);
+        for _ in 0..10 {
+            system.update(1.0);
+        }
+
+        // Back to the mutated starting point, not the origin
+        system.reset_state();
+        assert_eq!(system.position, DVec2::new(0.25, -0.5));
+        assert!((system.u - 0.95).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::*;
+use bevy::color::{Color, Hsva};
+use serde::{Deserialize, Serialize};
+
+const INITIAL_X: f64 = 0.5;
+
+/// Logistic map `x' = r x (1 - x)`, the classic period-doubling route to chaos. Orbits stay in
+/// `[0, 1]` for `r` in `[0, 4]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogisticMap {
+    pub r: f64,
+    pub x: f64,
+}
+
+impl LogisticMap {
+    pub fn new(r: f64) -> Self {
+        LogisticMap { r, x: INITIAL_X }
+    }
+}
+
+impl ChaoticSystem for LogisticMap {
+    fn name() -> &'static str {
+        "Logistic map"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        1
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["r".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.r += pos.first().copied().unwrap_or_default();
+    }
+
+    fn reset_state(&mut self) {
+        self.x = INITIAL_X;
+    }
+
+    fn update(&mut self, _dt: f64) {
+        self.x = self.r * self.x * (1.0 - self.x);
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        LogisticMap {
+            r: lerp_f64(self.r, other.r, t),
+            x: lerp_f64(self.x, other.x, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        if !self.x.is_finite() {
+            return Color::BLACK;
+        }
+        let x = self.x.clamp(0.0, 1.0) as f32;
+        Hsva::new(x * 300.0, 0.8, 0.3 + 0.7 * x, 1.0).into()
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        (self.x - other.x).abs()
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stays_in_unit_interval() {
+        let mut system = LogisticMap::new(4.0);
+        for _ in 0..1000 {
+            system.update(1.0);
+            assert!((0.0..=1.0).contains(&system.x), "{}", system.x);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::*;
+use bevy::color::Color;
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
+
+/// Lozi map, a piecewise-linear version of the Hénon map.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lozi {
+    pub a: f64,
+    pub b: f64,
+    pub position: DVec2,
+}
+
+impl Lozi {
+    pub fn new(a: f64, b: f64) -> Self {
+        Lozi {
+            a,
+            b,
+            position: DVec2::ZERO,
+        }
+    }
+
+    /// Parameters of the well-known chaotic attractor.
+    pub fn canonical() -> Self {
+        Self::new(1.7, 0.5)
+    }
+}
+
+impl ChaoticSystem for Lozi {
+    fn name() -> &'static str {
+        "Lozi"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["a".to_string(), "b".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.a += pos.first().copied().unwrap_or_default();
+        self.b += pos.get(1).copied().unwrap_or_default();
+    }
+
+    fn reset_state(&mut self) {
+        self.position = DVec2::ZERO;
+    }
+
+    fn update(&mut self, _dt: f64) {
+        let DVec2 { x, y } = self.position;
+
+        self.position = DVec2::new(1.0 - self.a * x.abs() + y, self.b * x);
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Lozi {
+            a: lerp_f64(self.a, other.a, t),
+            b: lerp_f64(self.b, other.b, t),
+            position: self.position.lerp(other.position, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        position_to_color(self.position, DVec2::ZERO, 1.0)
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.position.distance(other.position)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.position.x, self.position.y]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_extent(mut system: Lozi, steps: usize) -> f64 {
+        let mut extent: f64 = 0.0;
+        for _ in 0..steps {
+            system.update(1.0);
+            extent = extent.max(system.position.abs().max_element());
+        }
+        extent
+    }
+
+    #[test]
+    fn test_canonical_bounded_divergent_not() {
+        assert!(max_extent(Lozi::canonical(), 5000) < 2.0);
+        assert!(max_extent(Lozi::new(2.5, 0.5), 100) > 1e6);
+        assert!(max_extent(Lozi::new(1.7, 1.2), 100) > 1e6);
+    }
+}
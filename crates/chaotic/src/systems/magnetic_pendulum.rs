@@ -0,0 +1,232 @@
+use crate::*;
+use bevy::color::{Color, Hsva};
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
+
+/// Fixed magnet pulling the bob of a [`MagneticPendulum`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Magnet {
+    pub position: DVec2,
+    pub strength: f64,
+}
+
+impl Magnet {
+    pub fn new(position: DVec2, strength: f64) -> Self {
+        Magnet { position, strength }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum MagneticPendulumColorSchema {
+    /// Hue from the nearest magnet, brighter the closer the bob is to it.
+    #[default]
+    Nearest,
+    /// Hue from the magnet the bob came to rest over (see [`MagneticPendulum::settled_magnet`]),
+    /// black while it still moves. Draws the basins of attraction.
+    Settled { tol: f64 },
+}
+
+/// Pendulum bob swinging over a plane of magnets, projected onto the plane: pulled back to the
+/// origin by gravity, attracted by every magnet and slowed by friction until it rests over one of
+/// them. The magnet a starting position ends at draws famously fractal basin boundaries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagneticPendulum {
+    pub magnets: Vec<Magnet>,
+    /// Strength of the pull back towards the origin.
+    pub gravity: f64,
+    pub friction: f64,
+    /// Distance between the bob and the plane of magnets, softens the attraction right above them.
+    pub height: f64,
+    pub position: DVec2,
+    pub velocity: DVec2,
+    #[serde(default)]
+    pub color_schema: MagneticPendulumColorSchema,
+}
+
+impl MagneticPendulum {
+    pub fn new(magnets: Vec<Magnet>) -> Self {
+        MagneticPendulum {
+            magnets,
+            gravity: 0.2,
+            friction: 0.2,
+            height: 0.25,
+            position: DVec2::ZERO,
+            velocity: DVec2::ZERO,
+            color_schema: MagneticPendulumColorSchema::default(),
+        }
+    }
+
+    pub fn with_color_schema(mut self, color_schema: MagneticPendulumColorSchema) -> Self {
+        self.color_schema = color_schema;
+        self
+    }
+
+    /// The classic setup of three equal magnets evenly spaced on the unit circle.
+    pub fn three_magnets() -> Self {
+        let magnets = (0..3)
+            .map(|i| {
+                let angle = std::f64::consts::FRAC_PI_2 + std::f64::consts::TAU * i as f64 / 3.0;
+                Magnet::new(DVec2::from_angle(angle), 1.0)
+            })
+            .collect();
+        Self::new(magnets)
+    }
+
+    /// Index of the magnet closest to the bob.
+    pub fn nearest_magnet(&self) -> Option<usize> {
+        self.magnets
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                let a = a.position.distance_squared(self.position);
+                let b = b.position.distance_squared(self.position);
+                a.total_cmp(&b)
+            })
+            .map(|(i, _)| i)
+    }
+
+    /// Magnet the bob has come to rest over: both its speed and acceleration are below `tol`.
+    /// `None` while it still moves.
+    pub fn settled_magnet(&self, tol: f64) -> Option<usize> {
+        if self.velocity.length() >= tol || self.acceleration().length() >= tol {
+            return None;
+        }
+        self.nearest_magnet()
+    }
+
+    fn magnet_hue(&self, index: usize) -> f32 {
+        index as f32 / self.magnets.len() as f32 * 360.0
+    }
+
+    fn acceleration(&self) -> DVec2 {
+        let mut acceleration = -self.gravity * self.position - self.friction * self.velocity;
+        for magnet in &self.magnets {
+            let direction = magnet.position - self.position;
+            let distance_sq = direction.length_squared() + self.height * self.height;
+            acceleration += direction * (magnet.strength / (distance_sq * distance_sq.sqrt()));
+        }
+        acceleration
+    }
+}
+
+impl ChaoticSystem for MagneticPendulum {
+    fn name() -> &'static str {
+        "Magnetic pendulum"
+    }
+
+    fn suggested_dt() -> f64 {
+        0.01
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["x".to_string(), "y".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.position += DVec2::new(
+            pos.first().copied().unwrap_or_default(),
+            pos.get(1).copied().unwrap_or_default(),
+        );
+    }
+
+    fn update(&mut self, dt: f64) {
+        self.velocity += self.acceleration() * dt;
+        self.position += self.velocity * dt;
+    }
+
+    /// Magnets are taken from `self`.
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        MagneticPendulum {
+            magnets: self.magnets.clone(),
+            gravity: lerp_f64(self.gravity, other.gravity, t),
+            friction: lerp_f64(self.friction, other.friction, t),
+            height: lerp_f64(self.height, other.height, t),
+            position: self.position.lerp(other.position, t),
+            velocity: self.velocity.lerp(other.velocity, t),
+            color_schema: self.color_schema,
+        }
+    }
+
+    fn color(&self) -> Color {
+        if !self.position.is_finite() {
+            return Color::BLACK;
+        }
+
+        match self.color_schema {
+            MagneticPendulumColorSchema::Nearest => {
+                let Some(nearest) = self.nearest_magnet() else {
+                    return Color::BLACK;
+                };
+                let distance = self.magnets[nearest].position.distance(self.position);
+                let value = 1.0 / (1.0 + distance);
+
+                Hsva::new(self.magnet_hue(nearest), 0.8, value as f32, 1.0).into()
+            }
+
+            MagneticPendulumColorSchema::Settled { tol } => match self.settled_magnet(tol) {
+                Some(magnet) => Hsva::new(self.magnet_hue(magnet), 0.8, 1.0, 1.0).into(),
+                None => Color::BLACK,
+            },
+        }
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.position.distance(other.position)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![
+            self.position.x,
+            self.position.y,
+            self.velocity.x,
+            self.velocity.y,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settles_near_magnet() {
+        for i in 0..3 {
+            let mut system = MagneticPendulum::three_magnets();
+            let magnet = system.magnets[i].position;
+            system.position = magnet * 1.1;
+
+            for _ in 0..20000 {
+                system.update(0.01);
+            }
+
+            assert_eq!(system.nearest_magnet(), Some(i));
+            assert!(system.position.distance(magnet) < 0.05, "{:?}", system);
+            assert!(system.velocity.length() < 1e-3, "{:?}", system);
+        }
+    }
+
+    #[test]
+    fn test_settled_magnet() {
+        let magnets = vec![
+            Magnet::new(DVec2::new(-1.0, 0.0), 1.0),
+            Magnet::new(DVec2::new(1.0, 0.0), 1.0),
+        ];
+        let schema = MagneticPendulumColorSchema::Settled { tol: 1e-3 };
+        let mut system = MagneticPendulum::new(magnets).with_color_schema(schema);
+        system.position = DVec2::new(1.05, 0.1);
+
+        // Released away from the equilibrium, it is still moving
+        assert_eq!(system.settled_magnet(1e-3), None);
+        assert_eq!(system.color(), Color::BLACK);
+
+        for _ in 0..20000 {
+            system.update(0.01);
+        }
+        assert_eq!(system.settled_magnet(1e-3), Some(1));
+        assert_ne!(system.color(), Color::BLACK);
+    }
+}
@@ -1,17 +1,92 @@
 use crate::*;
 use bevy::color::{Color, Hsva};
 use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy)]
+/// Standard bailout radius of the escape test.
+pub(crate) const DEFAULT_ESCAPE_RADIUS: f64 = 2.0;
+
+pub(crate) fn default_escape_radius() -> f64 {
+    DEFAULT_ESCAPE_RADIUS
+}
+
+/// Coloring of [`MandelbrotColorSchema::Distance`], shared with the other escape-time fractals.
+pub(crate) fn distance_color(z: DVec2) -> Color {
+    // Preserve existing alpha (based on distance), but make RGB colorful
+    let alpha = 1.0 / (1.0 + z.length_squared() as f32);
+
+    // Hue from the complex argument, normalized to [0, 1)
+    let mut hue = (z.y.atan2(z.x) as f32) / (2.0 * std::f32::consts::PI);
+    if hue < 0.0 {
+        hue += 1.0;
+    }
+
+    // Saturation full, value depends slightly on alpha to give depth
+    let s = 0.95f32;
+    let v = (0.95f32 - 0.6f32 * alpha).clamp(0.1, 1.0);
+
+    Hsva::new(hue, s, v, alpha).into()
+}
+
+/// Coloring of [`MandelbrotColorSchema::OrbitTrap`]: close approaches glow warm and bright,
+/// distant orbits fade to dark blue.
+fn orbit_trap_color(trap_distance: f64) -> Color {
+    if !trap_distance.is_finite() {
+        return Color::BLACK;
+    }
+    let closeness = (1.0 / (1.0 + 8.0 * trap_distance)) as f32;
+    Hsva::new(240.0 * (1.0 - closeness), 0.85, closeness.sqrt(), 1.0).into()
+}
+
+/// Shape the orbit is measured against by [`MandelbrotColorSchema::OrbitTrap`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Trap {
+    Point(DVec2),
+    /// Both axes of the complex plane.
+    Cross,
+}
+
+impl Trap {
+    pub fn distance(&self, z: DVec2) -> f64 {
+        match *self {
+            Trap::Point(point) => z.distance(point),
+            Trap::Cross => z.x.abs().min(z.y.abs()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum MandelbrotColorSchema {
     Distance,
+    /// Colored by the closest approach of the orbit to `trap`, see [`Mandelbrot::trap_distance`].
+    OrbitTrap {
+        trap: Trap,
+    },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Mandelbrot {
     pub color_schema: MandelbrotColorSchema,
     pub z: DVec2,
     pub c: DVec2,
+    /// Orbits are considered escaped once `|z|` exceeds this radius, larger values smooth the
+    /// coloring of the exterior.
+    #[serde(default = "default_escape_radius")]
+    pub escape_radius: f64,
+    /// Number of updates since the last reset.
+    #[serde(default)]
+    pub iterations: usize,
+    /// Iteration at which `|z|` first exceeded `escape_radius`, kept even if `z` comes back.
+    #[serde(default)]
+    pub escape_iteration: Option<usize>,
+    /// Smallest distance of `z` to the trap of [`MandelbrotColorSchema::OrbitTrap`] since the last
+    /// reset, infinite before the first update or with other schemas.
+    #[serde(default = "default_trap_distance")]
+    pub trap_distance: f64,
+}
+
+fn default_trap_distance() -> f64 {
+    f64::INFINITY
 }
 
 impl Mandelbrot {
@@ -21,23 +96,94 @@ impl Mandelbrot {
             color_schema,
             z: DVec2::ZERO,
             c: DVec2::ZERO,
+            escape_radius: DEFAULT_ESCAPE_RADIUS,
+            iterations: 0,
+            escape_iteration: None,
+            trap_distance: f64::INFINITY,
         }
     }
 }
 
+impl Samples<Mandelbrot> {
+    /// Grid of [`Mandelbrot`] systems tiling the complex-plane rectangle of size `span` around
+    /// `center`, every `c` in the middle of its cell. The first axis runs along the real part, the
+    /// second along the imaginary one.
+    pub fn mandelbrot_region(center: DVec2, span: DVec2, dimensions: Dimensions) -> Self {
+        assert_eq!(dimensions.len(), 2, "Expected 2D dimensions for a region");
+
+        let dimensions = dimensions.non_empty();
+        let sizes = DVec2::new(dimensions[0] as f64, dimensions[1] as f64);
+        let cell = span / sizes;
+        let corner = center - span / 2.0;
+
+        let samples = dimensions
+            .iter()
+            .map(|pos| {
+                let mut system = Mandelbrot::new(MandelbrotColorSchema::Distance);
+                system.c = corner + (DVec2::new(pos[0] as f64, pos[1] as f64) + 0.5) * cell;
+                system
+            })
+            .collect();
+
+        Samples::from_vec(dimensions, samples)
+    }
+}
+
 impl ChaoticSystem for Mandelbrot {
+    fn name() -> &'static str {
+        "Mandelbrot"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["c.re".to_string(), "c.im".to_string()]
+    }
+
+    /// Points with `|c| > 2` escape on the first iteration.
+    fn mutation_descriptor(&self) -> Vec<MutationParam> {
+        self.param_labels()
+            .into_iter()
+            .map(|label| MutationParam::new(label, 1.0).with_range(-2.0, 2.0))
+            .collect()
+    }
+
     fn mutate(&mut self, pos: &[f64]) {
         self.c += DVec2::new(
-            pos.get(0).copied().unwrap_or_default(),
+            pos.first().copied().unwrap_or_default(),
             pos.get(1).copied().unwrap_or_default(),
         );
     }
 
+    fn reset_state(&mut self) {
+        self.z = DVec2::ZERO;
+        self.iterations = 0;
+        self.escape_iteration = None;
+        self.trap_distance = f64::INFINITY;
+    }
+
     fn update(&mut self, _dt: f64) {
         self.z = DVec2::new(
             self.z.x * self.z.x - self.z.y * self.z.y,
             2.0 * self.z.x * self.z.y,
         ) + self.c;
+        self.iterations += 1;
+
+        let length_squared = self.z.length_squared();
+        let outside = length_squared > self.escape_radius * self.escape_radius;
+        if self.escape_iteration.is_none() && (outside || length_squared.is_nan()) {
+            self.escape_iteration = Some(self.iterations);
+        }
+
+        if let MandelbrotColorSchema::OrbitTrap { trap } = self.color_schema {
+            self.trap_distance = self.trap_distance.min(trap.distance(self.z));
+        }
     }
 
     fn lerp(&self, other: &Self, t: f64) -> Self {
@@ -45,31 +191,187 @@ impl ChaoticSystem for Mandelbrot {
             color_schema: self.color_schema,
             z: self.z.lerp(other.z, t),
             c: self.c.lerp(other.c, t),
+            escape_radius: lerp_f64(self.escape_radius, other.escape_radius, t),
+            iterations: self.iterations,
+            escape_iteration: self.escape_iteration,
+            trap_distance: self.trap_distance,
         }
     }
 
     fn color(&self) -> Color {
         match self.color_schema {
-            MandelbrotColorSchema::Distance => {
-                // Preserve existing alpha (based on distance), but make RGB colorful
-                let alpha = 1.0 / (1.0 + self.z.length_squared() as f32);
+            MandelbrotColorSchema::Distance => distance_color(self.z),
+            MandelbrotColorSchema::OrbitTrap { .. } => orbit_trap_color(self.trap_distance),
+        }
+    }
 
-                // Hue from the complex argument, normalized to [0, 1)
-                let mut hue = (self.z.y.atan2(self.z.x) as f32) / (2.0 * std::f32::consts::PI);
-                if hue < 0.0 {
-                    hue += 1.0;
-                }
+    /// Opaque once escaped, otherwise growing with `|z|` towards the escape radius.
+    fn alpha(&self) -> f32 {
+        if self.escaped() {
+            return 1.0;
+        }
+        (self.z.length() / self.escape_radius).clamp(0.0, 1.0) as f32
+    }
 
-                // Saturation full, value depends slightly on alpha to give depth
-                let s = 0.95f32;
-                let v = (0.95f32 - 0.6f32 * alpha).clamp(0.1, 1.0);
+    fn escaped(&self) -> bool {
+        self.escape_iteration.is_some()
+    }
 
-                Hsva::new(hue, s, v, alpha).into()
-            }
-        }
+    /// Orbits stay within the escape radius until they escape, after which the bounds grow to
+    /// include `z`.
+    fn spatial_bounds(&self) -> Option<(DVec2, DVec2)> {
+        let radius = DVec2::splat(self.escape_radius);
+        Some((self.z.min(-radius), self.z.max(radius)))
     }
 
     fn distance(&self, other: &Self) -> f64 {
         (self.z - other.z).length_squared()
     }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.z.x, self.z.y]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_param_labels() {
+        let system = Mandelbrot::new(MandelbrotColorSchema::Distance);
+        assert_eq!(Mandelbrot::name(), "Mandelbrot");
+        assert_eq!(system.param_labels().len(), system.param_count());
+        assert_eq!(system.param_labels(), vec!["c.re", "c.im"]);
+    }
+
+    #[test]
+    fn test_mutation_descriptor() {
+        let system = Mandelbrot::new(MandelbrotColorSchema::Distance);
+        let descriptor = system.mutation_descriptor();
+        assert_eq!(descriptor.len(), system.param_count());
+        assert_eq!(descriptor[1].label, "c.im");
+        assert!(descriptor
+            .iter()
+            .all(|param| param.range == Some((-2.0, 2.0))));
+    }
+
+    #[test]
+    fn test_region_corners() {
+        let samples = Samples::mandelbrot_region(
+            DVec2::new(-0.5, 0.0),
+            DVec2::new(2.0, 1.0),
+            Dimensions::new(vec![4, 2]),
+        );
+        let c = |x: usize, y: usize| samples.samples[samples.dimensions.pos_to_index(&[x, y])].c;
+
+        assert_eq!(c(0, 0), DVec2::new(-1.25, -0.25));
+        assert_eq!(c(3, 0), DVec2::new(0.25, -0.25));
+        assert_eq!(c(0, 1), DVec2::new(-1.25, 0.25));
+        assert_eq!(c(3, 1), DVec2::new(0.25, 0.25));
+        assert!(samples.samples.iter().all(|system| system.z == DVec2::ZERO));
+    }
+
+    #[test]
+    fn test_orbit_trap_distance() {
+        let new = |trap, c| {
+            let mut system = Mandelbrot::new(MandelbrotColorSchema::OrbitTrap { trap });
+            system.c = c;
+            system
+        };
+
+        // Orbit 0.5, 0.75, 1.0625
+        let mut point = new(Trap::Point(DVec2::X), DVec2::new(0.5, 0.0));
+        assert_eq!(point.trap_distance, f64::INFINITY);
+        point.update_n(3, 1.0);
+        assert_eq!(point.trap_distance, 0.0625);
+        assert_ne!(point.color(), Color::BLACK);
+
+        // Orbit 0.5 + 0.5i, 0.5 + i, -0.25 + 1.5i
+        let mut cross = new(Trap::Cross, DVec2::new(0.5, 0.5));
+        cross.update_n(3, 1.0);
+        assert_eq!(cross.trap_distance, 0.25);
+
+        cross.reset_state();
+        assert_eq!(cross.trap_distance, f64::INFINITY);
+    }
+
+    #[test]
+    fn test_reset_state() {
+        let mut system = Mandelbrot::new(MandelbrotColorSchema::Distance);
+        system.mutate(&[-0.5, 0.3]);
+        for _ in 0..10 {
+            system.update(1.0);
+        }
+        assert_ne!(system.z, DVec2::ZERO);
+
+        system.reset_state();
+        assert_eq!(system.z, DVec2::ZERO);
+        assert_eq!(system.c, DVec2::new(-0.5, 0.3));
+    }
+
+    #[test]
+    fn test_escaped() {
+        let mut inside = Mandelbrot::new(MandelbrotColorSchema::Distance);
+        inside.mutate(&[-0.5, 0.0]);
+        let mut outside = Mandelbrot::new(MandelbrotColorSchema::Distance);
+        outside.mutate(&[1.0, 1.0]);
+
+        for _ in 0..50 {
+            inside.update(1.0);
+            outside.update(1.0);
+        }
+
+        assert!(!inside.escaped());
+        assert!(outside.escaped());
+    }
+
+    #[test]
+    fn test_escape_radius_delays_escape() {
+        let escape_iteration = |escape_radius: f64| {
+            let mut system = Mandelbrot::new(MandelbrotColorSchema::Distance);
+            system.escape_radius = escape_radius;
+            system.mutate(&[0.5, 0.5]);
+            for _ in 0..100 {
+                system.update(1.0);
+            }
+            system.escape_iteration
+        };
+
+        let standard = escape_iteration(2.0).unwrap();
+        let large = escape_iteration(100.0).unwrap();
+        assert!(large > standard, "{} <= {}", large, standard);
+        assert!(escape_iteration(1e6).unwrap() > large);
+    }
+
+    #[test]
+    fn test_alpha_lower_inside() {
+        let mut inside = Mandelbrot::new(MandelbrotColorSchema::Distance);
+        inside.mutate(&[-0.1, 0.1]);
+        let mut outside = Mandelbrot::new(MandelbrotColorSchema::Distance);
+        outside.mutate(&[0.5, 0.5]);
+
+        for _ in 0..50 {
+            inside.update(1.0);
+            outside.update(1.0);
+        }
+
+        assert!(inside.alpha() < 0.5, "{}", inside.alpha());
+        assert_eq!(outside.alpha(), 1.0);
+    }
+
+    #[test]
+    fn test_spatial_bounds() {
+        let mut system = Mandelbrot::new(MandelbrotColorSchema::Distance);
+        assert_eq!(
+            system.spatial_bounds(),
+            Some((DVec2::splat(-2.0), DVec2::splat(2.0)))
+        );
+
+        system.z = DVec2::new(3.0, -0.5);
+        assert_eq!(
+            system.spatial_bounds(),
+            Some((DVec2::splat(-2.0), DVec2::new(3.0, 2.0)))
+        );
+    }
 }
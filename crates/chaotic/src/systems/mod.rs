@@ -1,7 +1,38 @@
+mod arnold_cat;
+mod barnes_hut;
+mod bogdanov;
+mod chua;
+mod coupled_map_lattice;
+mod de_jong;
 mod double_pendulum;
+mod gumowski_mira;
+mod ikeda;
+mod logistic;
+mod lozi;
+mod magnetic_pendulum;
 mod mandelbrot;
+mod pickover;
+mod quadratic_map;
+mod sprott;
+mod thomas;
 mod three_body;
+mod tinkerbell;
 
+pub use arnold_cat::*;
+pub use bogdanov::*;
+pub use chua::*;
+pub use coupled_map_lattice::*;
+pub use de_jong::*;
 pub use double_pendulum::*;
+pub use gumowski_mira::*;
+pub use ikeda::*;
+pub use logistic::*;
+pub use lozi::*;
+pub use magnetic_pendulum::*;
 pub use mandelbrot::*;
+pub use pickover::*;
+pub use quadratic_map::*;
+pub use sprott::*;
+pub use thomas::*;
 pub use three_body::*;
+pub use tinkerbell::*;
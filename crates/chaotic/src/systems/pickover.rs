@@ -0,0 +1,126 @@
+use crate::*;
+use bevy::color::{Color, Hsva};
+use bevy::math::DVec3;
+use serde::{Deserialize, Serialize};
+
+const INITIAL_POSITION: DVec3 = DVec3::ZERO;
+
+/// Pickover's 3D attractor:
+/// `x' = sin(a y) - z cos(b x)`, `y' = z sin(c x) - cos(d y)`, `z' = sin x`.
+/// `|z| <= 1` after the first step, so the orbit stays within `[-2, 2]² × [-1, 1]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pickover {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub position: DVec3,
+}
+
+impl Pickover {
+    pub fn new(a: f64, b: f64, c: f64, d: f64) -> Self {
+        Pickover {
+            a,
+            b,
+            c,
+            d,
+            position: INITIAL_POSITION,
+        }
+    }
+
+    /// Parameters of the well-known chaotic attractor.
+    pub fn canonical() -> Self {
+        Self::new(2.24, 0.43, -0.65, -2.43)
+    }
+}
+
+impl ChaoticSystem for Pickover {
+    fn name() -> &'static str {
+        "Pickover"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["a".to_string(), "b".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.a += pos.first().copied().unwrap_or_default();
+        self.b += pos.get(1).copied().unwrap_or_default();
+    }
+
+    fn reset_state(&mut self) {
+        self.position = INITIAL_POSITION;
+    }
+
+    fn update(&mut self, _dt: f64) {
+        let DVec3 { x, y, z } = self.position;
+
+        self.position = DVec3::new(
+            (self.a * y).sin() - z * (self.b * x).cos(),
+            z * (self.c * x).sin() - (self.d * y).cos(),
+            x.sin(),
+        );
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Pickover {
+            a: lerp_f64(self.a, other.a, t),
+            b: lerp_f64(self.b, other.b, t),
+            c: lerp_f64(self.c, other.c, t),
+            d: lerp_f64(self.d, other.d, t),
+            position: self.position.lerp(other.position, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        let DVec3 { x, y, z } = self.position;
+        if !self.position.is_finite() {
+            return Color::BLACK;
+        }
+
+        let hue = normalize_angle(y.atan2(x));
+        let value = z.clamp(-1.0, 1.0) * 0.4 + 0.6;
+
+        Hsva::new((hue * 360.0) as f32, 0.85, value as f32, 1.0).into()
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.position.distance(other.position)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        self.position.to_array().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_bounded() {
+        let mut system = Pickover::canonical();
+        for _ in 0..5000 {
+            system.update(1.0);
+            let DVec3 { x, y, z } = system.position;
+            assert!(
+                x.abs() <= 2.0 && y.abs() <= 2.0 && z.abs() <= 1.0,
+                "{:?}",
+                system
+            );
+        }
+
+        // Still moving, not stuck on a fixed point
+        let prev = system.clone();
+        system.update(1.0);
+        assert!(system.distance(&prev) > 1e-6);
+    }
+}
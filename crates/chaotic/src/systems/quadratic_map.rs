@@ -0,0 +1,186 @@
+use super::mandelbrot::{default_escape_radius, distance_color, DEFAULT_ESCAPE_RADIUS};
+use crate::*;
+use bevy::color::Color;
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
+
+/// Product of two complex numbers stored as `(re, im)`.
+fn complex_mul(a: DVec2, b: DVec2) -> DVec2 {
+    DVec2::new(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x)
+}
+
+/// `z` raised to a non-negative integer power, by squaring.
+fn complex_powi(mut z: DVec2, mut power: u32) -> DVec2 {
+    let mut result = DVec2::X;
+    while power > 0 {
+        if power & 1 == 1 {
+            result = complex_mul(result, z);
+        }
+        z = complex_mul(z, z);
+        power >>= 1;
+    }
+    result
+}
+
+/// Multibrot map `z = z^power + c`, colored like [`Mandelbrot`]. Power 2 is the Mandelbrot set,
+/// higher powers give sets with `power - 1` fold rotational symmetry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuadraticMap {
+    pub power: u32,
+    pub z: DVec2,
+    pub c: DVec2,
+    /// See [`Mandelbrot::escape_radius`].
+    #[serde(default = "default_escape_radius")]
+    pub escape_radius: f64,
+    /// Number of updates since the last reset.
+    #[serde(default)]
+    pub iterations: usize,
+    /// Iteration at which `|z|` first exceeded `escape_radius`.
+    #[serde(default)]
+    pub escape_iteration: Option<usize>,
+}
+
+impl QuadraticMap {
+    pub fn new(power: u32) -> Self {
+        QuadraticMap {
+            power,
+            z: DVec2::ZERO,
+            c: DVec2::ZERO,
+            escape_radius: DEFAULT_ESCAPE_RADIUS,
+            iterations: 0,
+            escape_iteration: None,
+        }
+    }
+}
+
+impl ChaoticSystem for QuadraticMap {
+    fn name() -> &'static str {
+        "Multibrot"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["c.re".to_string(), "c.im".to_string()]
+    }
+
+    fn mutation_descriptor(&self) -> Vec<MutationParam> {
+        self.param_labels()
+            .into_iter()
+            .map(|label| MutationParam::new(label, 1.0).with_range(-2.0, 2.0))
+            .collect()
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.c += DVec2::new(
+            pos.first().copied().unwrap_or_default(),
+            pos.get(1).copied().unwrap_or_default(),
+        );
+    }
+
+    fn reset_state(&mut self) {
+        self.z = DVec2::ZERO;
+        self.iterations = 0;
+        self.escape_iteration = None;
+    }
+
+    fn update(&mut self, _dt: f64) {
+        self.z = complex_powi(self.z, self.power) + self.c;
+        self.iterations += 1;
+
+        let length_squared = self.z.length_squared();
+        let outside = length_squared > self.escape_radius * self.escape_radius;
+        if self.escape_iteration.is_none() && (outside || length_squared.is_nan()) {
+            self.escape_iteration = Some(self.iterations);
+        }
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        QuadraticMap {
+            power: self.power,
+            z: self.z.lerp(other.z, t),
+            c: self.c.lerp(other.c, t),
+            escape_radius: lerp_f64(self.escape_radius, other.escape_radius, t),
+            iterations: self.iterations,
+            escape_iteration: self.escape_iteration,
+        }
+    }
+
+    fn color(&self) -> Color {
+        distance_color(self.z)
+    }
+
+    fn alpha(&self) -> f32 {
+        if self.escaped() {
+            return 1.0;
+        }
+        (self.z.length() / self.escape_radius).clamp(0.0, 1.0) as f32
+    }
+
+    fn escaped(&self) -> bool {
+        self.escape_iteration.is_some()
+    }
+
+    fn spatial_bounds(&self) -> Option<(DVec2, DVec2)> {
+        let radius = DVec2::splat(self.escape_radius);
+        Some((self.z.min(-radius), self.z.max(radius)))
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        (self.z - other.z).length_squared()
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.z.x, self.z.y]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn escape_iteration(power: u32, c: DVec2) -> Option<usize> {
+        let mut system = QuadraticMap::new(power);
+        system.mutate(&[c.x, c.y]);
+        system.update_n(100, 1.0);
+        system.escape_iteration
+    }
+
+    #[test]
+    fn test_power_two_is_mandelbrot() {
+        let mut quadratic = QuadraticMap::new(2);
+        let mut mandelbrot = Mandelbrot::new(MandelbrotColorSchema::Distance);
+        quadratic.mutate(&[-0.1, 0.2]);
+        mandelbrot.mutate(&[-0.1, 0.2]);
+        for _ in 0..50 {
+            quadratic.update(1.0);
+            mandelbrot.update(1.0);
+            assert_eq!(quadratic.z, mandelbrot.z);
+        }
+    }
+
+    #[test]
+    fn test_cubic_symmetry() {
+        let points =
+            (0..21 * 21).map(|i| DVec2::new((i % 21) as f64, (i / 21) as f64) / 10.0 - 1.0);
+
+        let mut quadratic_asymmetric = false;
+        for c in points {
+            // The cubic set is symmetric under `c -> -c`, the quadratic one isn't
+            assert_eq!(escape_iteration(3, c), escape_iteration(3, -c), "{}", c);
+            quadratic_asymmetric |= escape_iteration(2, c) != escape_iteration(2, -c);
+        }
+        assert!(quadratic_asymmetric);
+
+        // `-1` is in the Mandelbrot set, its mirror isn't, and the cubic set contains neither
+        assert_eq!(escape_iteration(2, DVec2::NEG_X), None);
+        assert!(escape_iteration(2, DVec2::X).is_some());
+        assert!(escape_iteration(3, DVec2::NEG_X).is_some());
+    }
+}
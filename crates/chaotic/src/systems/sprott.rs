@@ -0,0 +1,196 @@
+use crate::*;
+use bevy::color::{Color, Hsva};
+use bevy::math::DVec3;
+use serde::{Deserialize, Serialize};
+
+/// Minimal chaotic flows catalogued by Sprott (1994), named by their letter in the paper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SprottVariant {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+}
+
+impl SprottVariant {
+    pub const ALL: [SprottVariant; 19] = [
+        SprottVariant::A,
+        SprottVariant::B,
+        SprottVariant::C,
+        SprottVariant::D,
+        SprottVariant::E,
+        SprottVariant::F,
+        SprottVariant::G,
+        SprottVariant::H,
+        SprottVariant::I,
+        SprottVariant::J,
+        SprottVariant::K,
+        SprottVariant::L,
+        SprottVariant::M,
+        SprottVariant::N,
+        SprottVariant::O,
+        SprottVariant::P,
+        SprottVariant::Q,
+        SprottVariant::R,
+        SprottVariant::S,
+    ];
+
+    /// Point in the basin of the attractor. `A` is conservative, so it starts on its chaotic sea.
+    pub fn initial_point(self) -> DVec3 {
+        match self {
+            SprottVariant::A => DVec3::new(0.0, 5.0, 0.0),
+            _ => DVec3::splat(0.05),
+        }
+    }
+
+    pub fn derivative(self, p: DVec3) -> DVec3 {
+        let DVec3 { x, y, z } = p;
+        let (dx, dy, dz) = match self {
+            SprottVariant::A => (y, -x + y * z, 1.0 - y * y),
+            SprottVariant::B => (y * z, x - y, 1.0 - x * y),
+            SprottVariant::C => (y * z, x - y, 1.0 - x * x),
+            SprottVariant::D => (-y, x + z, x * z + 3.0 * y * y),
+            SprottVariant::E => (y * z, x * x - y, 1.0 - 4.0 * x),
+            SprottVariant::F => (y + z, -x + 0.5 * y, x * x - z),
+            SprottVariant::G => (0.4 * x + z, x * z - y, -x + y),
+            SprottVariant::H => (-y + z * z, x + 0.5 * y, x - z),
+            SprottVariant::I => (-0.2 * y, x + z, x + y * y - z),
+            SprottVariant::J => (2.0 * z, -2.0 * y + z, -x + y + y * y),
+            SprottVariant::K => (x * y - z, x - y, x + 0.3 * z),
+            SprottVariant::L => (y + 3.9 * z, 0.9 * x * x - y, 1.0 - x),
+            SprottVariant::M => (-z, -x * x - y, 1.7 + 1.7 * x + y),
+            SprottVariant::N => (-2.0 * y, x + z * z, 1.0 + y - 2.0 * z),
+            SprottVariant::O => (y, x - z, x + x * z + 2.7 * y),
+            SprottVariant::P => (2.7 * y + z, -x + y * y, x + y),
+            SprottVariant::Q => (-z, x - y, 3.1 * x + y * y + 0.5 * z),
+            SprottVariant::R => (0.9 - y, 0.4 + z, x * y - z),
+            SprottVariant::S => (-x - 4.0 * y, x + z * z, 1.0 + x),
+        };
+        DVec3::new(dx, dy, dz)
+    }
+}
+
+/// One of the Sprott flows, mutated by shifting its starting point.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sprott {
+    pub variant: SprottVariant,
+    pub position: DVec3,
+}
+
+impl Sprott {
+    pub fn new(variant: SprottVariant) -> Self {
+        Sprott {
+            variant,
+            position: variant.initial_point(),
+        }
+    }
+}
+
+impl ChaoticSystem for Sprott {
+    fn name() -> &'static str {
+        "Sprott"
+    }
+
+    fn suggested_dt() -> f64 {
+        0.01
+    }
+
+    fn param_count(&self) -> usize {
+        3
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["x".to_string(), "y".to_string(), "z".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.position += DVec3::new(
+            pos.first().copied().unwrap_or_default(),
+            pos.get(1).copied().unwrap_or_default(),
+            pos.get(2).copied().unwrap_or_default(),
+        );
+    }
+
+    fn update(&mut self, dt: f64) {
+        self.position = rk4_step(self.position, dt, |p| self.variant.derivative(p));
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Sprott {
+            variant: self.variant,
+            position: self.position.lerp(other.position, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        let DVec3 { x, y, z } = self.position;
+        if !self.position.is_finite() {
+            return Color::BLACK;
+        }
+
+        let hue = normalize_angle(y.atan2(x));
+        let value = z / (z.abs() + 1.0) * 0.4 + 0.6;
+
+        Hsva::new((hue * 360.0) as f32, 0.85, value as f32, 1.0).into()
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.position.distance(other.position)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        self.position.to_array().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variants_bounded_and_chaotic() {
+        let dt = 0.01;
+
+        for variant in [SprottVariant::B, SprottVariant::S, SprottVariant::P] {
+            let mut system = Sprott::new(variant);
+            for _ in 0..20000 {
+                system.update(dt);
+                assert!(
+                    system.position.length() < 100.0,
+                    "{:?}: {:?}",
+                    variant,
+                    system.position
+                );
+            }
+
+            let exponent = largest_lyapunov(&system, 20000, dt, 1e-8);
+            assert!(exponent > 0.01, "{:?}: {}", variant, exponent);
+        }
+    }
+
+    #[test]
+    fn test_all_variants_finite() {
+        for variant in SprottVariant::ALL {
+            let mut system = Sprott::new(variant);
+            for _ in 0..2000 {
+                system.update(0.01);
+            }
+            assert!(system.position.is_finite(), "{:?}", variant);
+        }
+    }
+}
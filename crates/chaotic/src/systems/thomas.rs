@@ -0,0 +1,125 @@
+use crate::*;
+use bevy::color::{Color, Hsva};
+use bevy::math::DVec3;
+use serde::{Deserialize, Serialize};
+
+/// Thomas' cyclically symmetric attractor. Chaotic for small `b` (around `0.2`), every
+/// trajectory decays to the origin for `b > 1`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Thomas {
+    pub b: f64,
+    pub position: DVec3,
+}
+
+impl Thomas {
+    pub fn new(b: f64) -> Self {
+        Thomas {
+            b,
+            position: DVec3::new(0.1, 0.0, 0.0),
+        }
+    }
+
+    fn derivative(&self, p: DVec3) -> DVec3 {
+        DVec3::new(
+            p.y.sin() - self.b * p.x,
+            p.z.sin() - self.b * p.y,
+            p.x.sin() - self.b * p.z,
+        )
+    }
+}
+
+impl ChaoticSystem for Thomas {
+    fn name() -> &'static str {
+        "Thomas"
+    }
+
+    fn suggested_dt() -> f64 {
+        0.05
+    }
+
+    fn param_count(&self) -> usize {
+        3
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["x".to_string(), "y".to_string(), "z".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.position += DVec3::new(
+            pos.first().copied().unwrap_or_default(),
+            pos.get(1).copied().unwrap_or_default(),
+            pos.get(2).copied().unwrap_or_default(),
+        );
+    }
+
+    fn update(&mut self, dt: f64) {
+        self.position = rk4_step(self.position, dt, |p| self.derivative(p));
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Thomas {
+            b: lerp_f64(self.b, other.b, t),
+            position: self.position.lerp(other.position, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        let DVec3 { x, y, z } = self.position;
+        if !self.position.is_finite() {
+            return Color::BLACK;
+        }
+
+        let hue = normalize_angle(y.atan2(x));
+        // Trajectories stay within `|z| <= 1 / b`
+        let value = (z * self.b).clamp(-1.0, 1.0) * 0.4 + 0.6;
+
+        Hsva::new((hue * 360.0) as f32, 0.85, value as f32, 1.0).into()
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.position.distance(other.position)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        self.position.to_array().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_b_chaotic() {
+        let mut system = Thomas::new(0.18);
+        let dt = 0.05;
+
+        let mut min_step = f64::MAX;
+        for i in 0..20000 {
+            let prev = system.clone();
+            system.update(dt);
+            assert!(system.position.is_finite());
+            assert!(system.position.length() < 3.0f64.sqrt() / system.b + 1.0);
+            if i > 1000 {
+                min_step = min_step.min(system.distance(&prev));
+            }
+        }
+
+        assert!(system.position.length() > 0.1);
+        assert!(min_step > 1e-6, "{}", min_step);
+        assert!(largest_lyapunov(&system, 20000, dt, 1e-8) > 0.0);
+    }
+
+    #[test]
+    fn test_large_b_damps_to_origin() {
+        let mut system = Thomas::new(1.5);
+        system.mutate(&[0.5, -0.3, 0.8]);
+
+        for _ in 0..2000 {
+            system.update(0.05);
+        }
+
+        assert!(system.position.length() < 1e-6, "{:?}", system.position);
+    }
+}
@@ -1,24 +1,75 @@
+use super::barnes_hut::QuadTree;
 use crate::*;
 use bevy::color::{Color, Hsva, LinearRgba};
+use bevy::log::warn_once;
 use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
 
 const EPSILON: f64 = 1e-5;
 
-#[derive(Debug, Clone, Copy)]
+/// Gravity of a body of `mass` at `direction` from a body of unit mass, `g` is negative for
+/// repulsion. `softening` is added to the distance in quadrature, bounding the force between close
+/// bodies. Bodies closer than `EPSILON` don't interact, so the force is never `NaN`.
+pub(super) fn gravity(direction: DVec2, g: f64, mass: f64, softening: f64) -> DVec2 {
+    let distance_sq = direction.length_squared();
+    if distance_sq < EPSILON {
+        return DVec2::ZERO; // Avoid division by zero
+    }
+
+    let softened_sq = distance_sq + softening * softening;
+    direction * (g * mass / (softened_sq * softened_sq.sqrt()))
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum NBodyColorSchema {
     VelocityToRgb { v0: f64 },
     DistanceToLightness { factor: f64 },
     FirstBodyVelToGB,
 }
 
-#[derive(Debug, Clone)]
+fn default_dist_scale() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NBody {
+    /// Gravitational constant, negative values make bodies repel each other.
     pub g: f64,
     pub bodies: Vec<Body>,
     pub color_schema: NBodyColorSchema,
+    /// Squared spread of the bodies at which the color alpha drops to a half, see
+    /// [`NBody::spread_alpha`].
+    #[serde(default = "default_dist_scale")]
+    pub dist_scale: f64,
+    /// Radius of a soft wall around the origin bodies can't leave, see [`NBody::with_max_extent`].
+    #[serde(default)]
+    pub max_extent: Option<f64>,
+    /// Hookean springs between pairs of bodies, applied on top of gravity.
+    #[serde(default)]
+    pub springs: Vec<Spring>,
+    /// Radius of every body for elastic collisions, see [`NBody::with_collision_radius`]. `0`
+    /// disables collisions.
+    #[serde(default)]
+    pub collision_radius: f64,
+    /// Gravity between bodies further apart than this is ignored, see
+    /// [`NBody::with_cutoff_radius`].
+    #[serde(default)]
+    pub cutoff_radius: Option<f64>,
+    /// Softening length of gravity, see [`NBody::with_softening`].
+    #[serde(default)]
+    pub softening: f64,
+}
+
+/// Hookean spring connecting bodies `a` and `b`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Spring {
+    pub a: usize,
+    pub b: usize,
+    pub rest_length: f64,
+    pub stiffness: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Body {
     pub position: DVec2,
     pub velocity: DVec2,
@@ -35,20 +86,237 @@ impl Body {
     }
 }
 
+impl Body {
+    /// Clamps the body to the circle of radius `limit` and removes the outward velocity.
+    fn contain(&mut self, limit: f64) {
+        let distance = self.position.length();
+        if distance <= limit {
+            return;
+        }
+
+        let normal = self.position / distance;
+        self.position = normal * limit;
+
+        let outward_speed = self.velocity.dot(normal);
+        if outward_speed > 0.0 {
+            self.velocity -= normal * outward_speed;
+        }
+    }
+}
+
 impl NBody {
     pub fn new(g: f64, bodies: Vec<Body>, color_schema: NBodyColorSchema) -> Self {
         NBody {
             g,
             bodies,
             color_schema,
+            dist_scale: default_dist_scale(),
+            max_extent: None,
+            springs: Vec::new(),
+            collision_radius: 0.0,
+            cutoff_radius: None,
+            softening: 0.0,
         }
     }
 
+    /// `n` bodies of `mass` evenly spaced on a circle of `radius` around the origin, the first one
+    /// on the X axis, all moving counterclockwise along the circle at `tangential_velocity`.
+    pub fn ring(n: usize, radius: f64, mass: f64, g: f64, tangential_velocity: f64) -> Self {
+        let bodies = (0..n)
+            .map(|i| {
+                let direction = DVec2::from_angle(std::f64::consts::TAU * i as f64 / n as f64);
+                Body::new(
+                    mass,
+                    direction * radius,
+                    direction.perp() * tangential_velocity,
+                )
+            })
+            .collect();
+        Self::new(g, bodies, NBodyColorSchema::VelocityToRgb { v0: 1.0 })
+    }
+
+    /// Connects bodies `a` and `b` with a spring.
+    pub fn with_spring(mut self, a: usize, b: usize, rest_length: f64, stiffness: f64) -> Self {
+        self.springs.push(Spring {
+            a,
+            b,
+            rest_length,
+            stiffness,
+        });
+        self
+    }
+
+    /// Sets [`NBody::dist_scale`], a larger scale keeps wide systems visible.
+    pub fn with_dist_scale(mut self, dist_scale: f64) -> Self {
+        self.dist_scale = dist_scale;
+        self
+    }
+
+    /// Keeps bodies within `limit` of the origin: a body reaching the boundary is clamped to it
+    /// and loses its outward velocity.
+    pub fn with_max_extent(mut self, limit: f64) -> Self {
+        self.max_extent = Some(limit);
+        self
+    }
+
+    /// Makes bodies disks of `radius` that bounce off each other elastically, exchanging momentum
+    /// along the line between their centers.
+    pub fn with_collision_radius(mut self, radius: f64) -> Self {
+        self.collision_radius = radius;
+        self
+    }
+
+    /// Skips gravity between bodies more than `radius` apart, trading accuracy for speed in systems
+    /// with many bodies. Springs are always applied.
+    pub fn with_cutoff_radius(mut self, radius: f64) -> Self {
+        self.cutoff_radius = Some(radius);
+        self
+    }
+
+    /// Softens gravity to `g * m1 * m2 * r / (r² + softening²)^(3/2)` (Plummer softening), so close
+    /// encounters don't fling bodies apart with huge velocities.
+    pub fn with_softening(mut self, softening: f64) -> Self {
+        self.softening = softening;
+        self
+    }
+
+    /// Turns gravity into repulsion of the same strength by making [`NBody::g`] negative.
+    pub fn with_repulsion(mut self) -> Self {
+        self.g = -self.g.abs();
+        self
+    }
+
     #[inline]
     pub fn iter(&self) -> impl Iterator<Item = &Body> {
         self.bodies.iter()
     }
 
+    /// Returns one color per body: hue from the velocity direction and value from the speed.
+    pub fn body_colors(&self) -> Vec<Color> {
+        self.iter()
+            .map(|body| {
+                let angle = body.velocity.y.atan2(body.velocity.x); // [-π, π]
+                let hue = ((angle / std::f64::consts::TAU) + 1.0) % 1.0; // [0,1)
+                let speed = body.velocity.length();
+                let val = speed / (speed + 1.0);
+
+                Hsva::new(hue as f32 * 360.0, 1.0, val as f32, 1.0).into()
+            })
+            .collect()
+    }
+
+    /// Total force acting on the body `i` at the current positions.
+    fn force(&self, i: usize) -> DVec2 {
+        let body_i = &self.bodies[i];
+        let cutoff_sq = self
+            .cutoff_radius
+            .map_or(f64::INFINITY, |radius| radius * radius);
+
+        let mut force = DVec2::ZERO;
+        for (j, body_j) in self.bodies.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+
+            let direction = body_j.position - body_i.position;
+            if direction.length_squared() > cutoff_sq {
+                continue;
+            }
+
+            force += gravity(direction, self.g, body_j.mass, self.softening) * body_i.mass;
+        }
+
+        force + self.spring_force(i)
+    }
+
+    /// Force of the springs attached to the body `i`.
+    fn spring_force(&self, i: usize) -> DVec2 {
+        let body_i = &self.bodies[i];
+
+        let mut force = DVec2::ZERO;
+        for spring in &self.springs {
+            let other = if spring.a == i {
+                spring.b
+            } else if spring.b == i {
+                spring.a
+            } else {
+                continue;
+            };
+            let Some(body_j) = self.bodies.get(other) else {
+                continue;
+            };
+
+            let direction = body_j.position - body_i.position;
+            let distance = direction.length();
+            if distance < EPSILON {
+                continue;
+            }
+
+            force += direction / distance * spring.stiffness * (distance - spring.rest_length);
+        }
+
+        force
+    }
+
+    /// Advances the system like [`ChaoticSystem::update`], but approximates gravity with a
+    /// Barnes-Hut quadtree: cells of bodies seen under an angle smaller than `theta` act as a
+    /// single body at their center of mass. This costs O(n log n) instead of O(n²) per step,
+    /// `theta = 0` is exact. All bodies are moved by the forces at the start of the step and
+    /// [`NBody::cutoff_radius`] is ignored.
+    pub fn update_barnes_hut(&mut self, dt: f64, theta: f64) {
+        let forces = self.barnes_hut_forces(theta);
+        for (body, force) in self.bodies.iter_mut().zip(forces) {
+            body.velocity += force / body.mass * dt;
+            body.position += body.velocity * dt;
+
+            if let Some(limit) = self.max_extent {
+                body.contain(limit);
+            }
+        }
+
+        if self.collision_radius > 0.0 {
+            self.collide();
+        }
+    }
+
+    /// Total force acting on every body, with gravity approximated by a Barnes-Hut quadtree.
+    fn barnes_hut_forces(&self, theta: f64) -> Vec<DVec2> {
+        let tree = QuadTree::new(&self.bodies);
+        (0..self.bodies.len())
+            .map(|i| {
+                tree.force(i, &self.bodies, self.g, theta, self.softening) + self.spring_force(i)
+            })
+            .collect()
+    }
+
+    /// Resolves elastic collisions of every pair of overlapping bodies moving towards each other.
+    fn collide(&mut self) {
+        let min_distance_sq = (2.0 * self.collision_radius).powi(2);
+
+        for i in 0..self.bodies.len() {
+            for j in i + 1..self.bodies.len() {
+                let (head, tail) = self.bodies.split_at_mut(j);
+                let (a, b) = (&mut head[i], &mut tail[0]);
+
+                let direction = b.position - a.position;
+                let distance_sq = direction.length_squared();
+                if distance_sq >= min_distance_sq || distance_sq < EPSILON * EPSILON {
+                    continue;
+                }
+
+                let normal = direction / distance_sq.sqrt();
+                let approach_speed = (a.velocity - b.velocity).dot(normal);
+                if approach_speed <= 0.0 {
+                    continue; // Already separating
+                }
+
+                let total_mass = a.mass + b.mass;
+                a.velocity -= normal * (2.0 * b.mass / total_mass * approach_speed);
+                b.velocity += normal * (2.0 * a.mass / total_mass * approach_speed);
+            }
+        }
+    }
+
     /// Returns a maximum distance between bodies in the system.
     fn max_dist_sq(&self) -> f64 {
         let mut max_dist_sq = 0.0f64;
@@ -64,9 +332,43 @@ impl NBody {
         }
         max_dist_sq
     }
+
+    /// Color alpha falling off with the spread of the bodies: `dist_scale / (max_dist_sq +
+    /// dist_scale)`, so larger scales keep wide systems visible.
+    pub fn spread_alpha(&self) -> f64 {
+        self.dist_scale / (self.max_dist_sq() + self.dist_scale)
+    }
 }
 
 impl ChaoticSystem for NBody {
+    fn name() -> &'static str {
+        "N-Body"
+    }
+
+    fn suggested_dt() -> f64 {
+        0.33
+    }
+
+    fn param_count(&self) -> usize {
+        self.bodies.len() * 4
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        (0..self.bodies.len())
+            .flat_map(|i| ["vx", "vy", "x", "y"].map(|field| format!("body{}.{}", i, field)))
+            .collect()
+    }
+
+    /// Positions are mutated ten times slower than velocities: bodies start about one unit apart,
+    /// so the same offset changes them much more.
+    fn mutation_descriptor(&self) -> Vec<MutationParam> {
+        self.param_labels()
+            .into_iter()
+            .enumerate()
+            .map(|(i, label)| MutationParam::new(label, if i % 4 < 2 { 1.0 } else { 0.1 }))
+            .collect()
+    }
+
     fn mutate(&mut self, pos: &[f64]) {
         for (i, &mutation) in pos.iter().enumerate() {
             let Some(body) = self.bodies.get_mut(i / 4) else {
@@ -87,39 +389,32 @@ impl ChaoticSystem for NBody {
 
     fn update(&mut self, dt: f64) {
         for i in 0..self.bodies.len() {
-            let body_i = &self.bodies[i];
-
-            let mut force = DVec2::ZERO;
-            for (j, body_j) in self.bodies.iter().enumerate() {
-                if i == j {
-                    continue;
-                }
-
-                let direction = body_j.position - body_i.position;
-                let distance_sq = direction.length_squared();
-                if distance_sq < EPSILON {
-                    continue; // Avoid division by zero
-                }
-                let force_magnitude = self.g * body_j.mass * body_i.mass / distance_sq;
-
-                force += direction.normalize() * force_magnitude;
-            }
-
-            let acceleration = force / body_i.mass;
+            let acceleration = self.force(i) / self.bodies[i].mass;
 
             let body_i = &mut self.bodies[i];
             body_i.velocity += acceleration * dt;
             body_i.position += body_i.velocity * dt;
+
+            if let Some(limit) = self.max_extent {
+                body_i.contain(limit);
+            }
+        }
+
+        if self.collision_radius > 0.0 {
+            self.collide();
         }
     }
 
+    /// Bodies missing in `other` are kept as they are in `self`.
     fn lerp(&self, other: &Self, t: f64) -> Self {
-        assert_eq!(
-            self.bodies.len(),
-            other.bodies.len(),
-            "Mismatched body count"
-        );
-        let bodies = self
+        if self.bodies.len() != other.bodies.len() {
+            warn_once!(
+                "Interpolating N-Body systems with {} and {} bodies",
+                self.bodies.len(),
+                other.bodies.len()
+            );
+        }
+        let mut bodies = self
             .bodies
             .iter()
             .zip(&other.bodies)
@@ -129,11 +424,18 @@ impl ChaoticSystem for NBody {
                 mass: lerp_f64(b1.mass, b2.mass, t),
             })
             .collect::<Vec<_>>();
+        bodies.extend_from_slice(&self.bodies[bodies.len()..]);
 
         NBody {
             color_schema: self.color_schema,
+            dist_scale: self.dist_scale,
             g: lerp_f64(self.g, other.g, t),
             bodies,
+            max_extent: self.max_extent,
+            springs: self.springs.clone(),
+            collision_radius: self.collision_radius,
+            cutoff_radius: self.cutoff_radius,
+            softening: self.softening,
         }
     }
 
@@ -177,7 +479,7 @@ impl ChaoticSystem for NBody {
                 let v0 = if v0 > 0.0 { v0 } else { 1.0 };
                 let val = (rms / (rms + v0)).clamp(0.0, 1.0);
 
-                let dist = 1.0 / (self.max_dist_sq() + 1.0);
+                let dist = self.spread_alpha();
 
                 Hsva::new(hue as f32, sat as f32, val as f32, dist as f32).into()
             }
@@ -189,12 +491,12 @@ impl ChaoticSystem for NBody {
             }
 
             NBodyColorSchema::FirstBodyVelToGB => {
-                let Some(body) = self.bodies.get(0) else {
+                let Some(body) = self.bodies.first() else {
                     return Color::BLACK;
                 };
                 let velocity = body.velocity;
 
-                let dist = 1.0 / (self.max_dist_sq() + 1.0);
+                let dist = self.spread_alpha();
 
                 LinearRgba::new(
                     1.0 / (1.0 + velocity.x.abs() as f32),
@@ -207,13 +509,558 @@ impl ChaoticSystem for NBody {
         }
     }
 
+    /// Grows as the bodies spread apart, bound systems fade out.
+    fn alpha(&self) -> f32 {
+        let max_dist_sq = self.max_dist_sq();
+        if !max_dist_sq.is_finite() {
+            return 1.0;
+        }
+        (max_dist_sq / (max_dist_sq + 1.0)) as f32
+    }
+
+    /// Average velocity difference of the bodies both systems have.
     fn distance(&self, other: &Self) -> f64 {
+        if self.bodies.len() != other.bodies.len() {
+            warn_once!(
+                "Comparing N-Body systems with {} and {} bodies",
+                self.bodies.len(),
+                other.bodies.len()
+            );
+        }
+
         let mut total_distance = 0.0;
+        let mut count = 0;
         for (body_a, body_b) in self.iter().zip(other.iter()) {
             let distance = body_a.velocity.distance(body_b.velocity);
             total_distance += distance;
+            count += 1;
+        }
+
+        if count == 0 {
+            return 0.0;
         }
+        total_distance / count as f64 // Average distance
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        self.iter()
+            .flat_map(|body| {
+                [
+                    body.velocity.x,
+                    body.velocity.y,
+                    body.position.x,
+                    body.position.y,
+                ]
+            })
+            .collect()
+    }
+
+    /// Box around the finite body positions, `None` if there are none.
+    fn spatial_bounds(&self) -> Option<(DVec2, DVec2)> {
+        self.iter()
+            .map(|body| body.position)
+            .filter(|position| position.is_finite())
+            .fold(None, |bounds, position| match bounds {
+                None => Some((position, position)),
+                Some((min, max)) => Some((min.min(position), max.max(position))),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn three_bodies() -> NBody {
+        NBody::new(
+            1.0,
+            vec![
+                Body::new(0.1, DVec2::X, DVec2::ZERO),
+                Body::new(0.1, DVec2::Y, DVec2::ZERO),
+                Body::new(0.1, DVec2::NEG_X, DVec2::ZERO),
+            ],
+            NBodyColorSchema::VelocityToRgb { v0: 1.0 },
+        )
+    }
+
+    #[test]
+    fn test_param_labels() {
+        let system = three_bodies();
+        let labels = system.param_labels();
+        assert_eq!(labels.len(), system.param_count());
+        assert_eq!(labels.len(), 12);
+        assert_eq!(&labels[..4], ["body0.vx", "body0.vy", "body0.x", "body0.y"]);
+        assert_eq!(labels[11], "body2.y");
+    }
+
+    #[test]
+    fn test_mutation_descriptor() {
+        let system = three_bodies();
+        let descriptor = system.mutation_descriptor();
+        assert_eq!(descriptor.len(), system.param_count());
+        assert_eq!(
+            descriptor
+                .iter()
+                .map(|param| &param.label)
+                .collect::<Vec<_>>(),
+            system.param_labels().iter().collect::<Vec<_>>()
+        );
+        assert_eq!(descriptor[0].scale, 1.0);
+        assert_eq!(descriptor[2].scale, 0.1);
+        assert!(descriptor.iter().all(|param| param.range.is_none()));
+    }
+
+    #[test]
+    fn test_dist_scale_raises_alpha() {
+        let alpha = |dist_scale: f64| {
+            let mut system = three_bodies().with_dist_scale(dist_scale);
+            let velocity_alpha = system.color().to_linear().alpha;
+            system.color_schema = NBodyColorSchema::FirstBodyVelToGB;
+            assert_eq!(system.color().to_linear().alpha, velocity_alpha);
+            velocity_alpha
+        };
+
+        // Spread of `2` along the x axis
+        assert!((alpha(1.0) - 0.2).abs() < 1e-6);
+        assert!(alpha(10.0) > alpha(1.0));
+        assert!(alpha(100.0) > alpha(10.0));
+    }
+
+    #[test]
+    fn test_body_colors() {
+        let system = three_bodies();
+        assert_eq!(system.body_colors().len(), system.bodies.len());
+
+        let empty = NBody::new(1.0, vec![], NBodyColorSchema::FirstBodyVelToGB);
+        assert!(empty.body_colors().is_empty());
+    }
+
+    #[test]
+    fn test_reset_state_keeps_bodies() {
+        let mut system = three_bodies();
+        system.update(0.1);
+        let expected = system.state_vector();
+
+        system.reset_state();
+        assert_eq!(system.state_vector(), expected);
+    }
+
+    #[test]
+    fn test_spring_oscillates() {
+        let rest_length = 1.0;
+        let mut system = NBody::new(
+            0.0,
+            vec![
+                Body::new(1.0, DVec2::new(-0.75, 0.0), DVec2::ZERO),
+                Body::new(1.0, DVec2::new(0.75, 0.0), DVec2::ZERO),
+            ],
+            NBodyColorSchema::VelocityToRgb { v0: 1.0 },
+        )
+        .with_spring(0, 1, rest_length, 4.0);
+
+        let length = |system: &NBody| {
+            system.bodies[0]
+                .position
+                .distance(system.bodies[1].position)
+        };
+
+        let mut crossings = 0;
+        let mut stretched = length(&system) > rest_length;
+        let mut sum = 0.0;
+        let steps = 10000;
+        for _ in 0..steps {
+            system.update(0.001);
+            let current = length(&system);
+            assert!(current > 0.4 && current < 1.6, "{}", current);
+            sum += current;
+
+            if (current > rest_length) != stretched {
+                stretched = !stretched;
+                crossings += 1;
+            }
+        }
+
+        assert!(crossings >= 4, "{}", crossings);
+        assert!((sum / steps as f64 - rest_length).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_max_extent() {
+        let limit = 5.0;
+        let mut system = NBody::new(
+            1.0,
+            vec![
+                Body::new(0.1, DVec2::ZERO, DVec2::ZERO),
+                Body::new(0.1, DVec2::X, DVec2::new(100.0, 10.0)),
+            ],
+            NBodyColorSchema::VelocityToRgb { v0: 1.0 },
+        )
+        .with_max_extent(limit);
+
+        for _ in 0..100 {
+            system.update(0.1);
+            for body in system.iter() {
+                assert!(body.position.length() <= limit + 1e-9);
+            }
+        }
+
+        // Outward velocity is removed at the wall, tangential motion is kept
+        let body = &system.bodies[1];
+        assert!(body.velocity.dot(body.position.normalize()) <= 1e-9);
+    }
+
+    #[test]
+    fn test_elastic_collision_swaps_velocities() {
+        let (left, right) = (DVec2::new(1.0, 0.0), DVec2::new(-0.5, 0.0));
+        let run = |collision_radius: f64| {
+            let mut system = NBody::new(
+                0.0,
+                vec![
+                    Body::new(1.0, DVec2::new(-1.0, 0.0), left),
+                    Body::new(1.0, DVec2::new(1.0, 0.0), right),
+                ],
+                NBodyColorSchema::VelocityToRgb { v0: 1.0 },
+            )
+            .with_collision_radius(collision_radius);
+            for _ in 0..300 {
+                system.update(0.01);
+            }
+            system
+        };
+
+        let system = run(0.1);
+        assert_eq!(system.bodies[0].velocity, right);
+        assert_eq!(system.bodies[1].velocity, left);
+        assert!(system.bodies[0].position.x < system.bodies[1].position.x);
+
+        // Without a radius bodies pass through each other
+        let ghosts = run(0.0);
+        assert_eq!(ghosts.bodies[0].velocity, left);
+        assert!(ghosts.bodies[0].position.x > ghosts.bodies[1].position.x);
+    }
+
+    #[test]
+    fn test_alpha_higher_when_diverging() {
+        let mut bound = three_bodies();
+        let mut diverging = three_bodies();
+        for body in &mut diverging.bodies {
+            body.velocity = body.position * 10.0;
+        }
+
+        for _ in 0..100 {
+            bound.update(0.01);
+            diverging.update(0.01);
+        }
+
+        assert!(bound.alpha() < diverging.alpha());
+        assert!(diverging.alpha() > 0.99);
+    }
+
+    #[test]
+    fn test_repulsion_pushes_bodies_apart() {
+        let bodies = vec![
+            Body::new(1.0, DVec2::new(-0.01, 0.0), DVec2::ZERO),
+            Body::new(1.0, DVec2::new(0.01, 0.0), DVec2::ZERO),
+        ];
+        let schema = NBodyColorSchema::VelocityToRgb { v0: 1.0 };
+        let mut system = NBody::new(1.0, bodies, schema)
+            .with_repulsion()
+            .with_softening(0.1);
+        assert_eq!(system.g, -1.0);
+
+        let mut distance = 0.02;
+        let mut speed = 0.0;
+        for _ in 0..1000 {
+            system.update(0.01);
+            let [a, b] = [&system.bodies[0], &system.bodies[1]];
+            assert!(a.position.is_finite() && a.velocity.is_finite());
+            assert!(b.position.is_finite() && b.velocity.is_finite());
+
+            let new_distance = a.position.distance(b.position);
+            assert!(new_distance > distance);
+            assert!(a.velocity.length() >= speed);
+            distance = new_distance;
+            speed = a.velocity.length();
+        }
+        // Pushed apart along the X axis
+        assert!(system.bodies[0].velocity.x < 0.0);
+        assert!(system.bodies[1].velocity.x > 0.0);
+        assert!(system.bodies[0].velocity.y.abs() < 1e-12);
+
+        // Softening bounds the force between close bodies
+        let close = vec![
+            Body::new(1.0, DVec2::new(0.0, 0.0), DVec2::ZERO),
+            Body::new(1.0, DVec2::new(0.005, 0.0), DVec2::ZERO),
+        ];
+        let soft = NBody::new(-1.0, close, schema).with_softening(0.1);
+        assert!(soft.force(0).length() < 10.0);
+        let hard = soft.clone().with_softening(0.0);
+        assert!(hard.force(0).length() > 1e4);
+    }
+
+    #[test]
+    fn test_cutoff_radius_ignores_distant_bodies() {
+        let near = vec![
+            Body::new(1.0, DVec2::ZERO, DVec2::ZERO),
+            Body::new(2.0, DVec2::new(0.5, 0.0), DVec2::ZERO),
+        ];
+        let mut bodies = near.clone();
+        bodies.push(Body::new(5.0, DVec2::new(0.0, 3.0), DVec2::ZERO));
+        bodies.push(Body::new(5.0, DVec2::new(-4.0, 0.0), DVec2::ZERO));
+
+        let schema = NBodyColorSchema::VelocityToRgb { v0: 1.0 };
+        let exact = NBody::new(1.0, bodies.clone(), schema);
+        let cut = NBody::new(1.0, bodies, schema).with_cutoff_radius(1.0);
+        let only_near = NBody::new(1.0, near, schema);
+
+        assert_eq!(cut.force(0), only_near.force(0));
+        assert_eq!(cut.force(1), only_near.force(1));
+        assert_ne!(exact.force(0), only_near.force(0));
+        // Distant bodies are out of range of each other too
+        assert_eq!(cut.force(2), DVec2::ZERO);
+        assert_eq!(cut.force(3), DVec2::ZERO);
+    }
+
+    #[test]
+    fn test_barnes_hut_matches_exact_forces() {
+        // Deterministic scatter of bodies with varying masses
+        let bodies = (0..200)
+            .map(|i| {
+                let t = i as f64;
+                let position = DVec2::new((t * 12.9898).sin(), (t * 78.233).sin()) * 10.0;
+                Body::new(0.5 + (t * 0.37).fract(), position, DVec2::ZERO)
+            })
+            .collect();
+        let system = NBody::new(1.0, bodies, NBodyColorSchema::VelocityToRgb { v0: 1.0 });
+
+        let forces = system.barnes_hut_forces(0.1);
+        for (i, approx) in forces.iter().enumerate() {
+            let exact = system.force(i);
+            let error = approx.distance(exact) / exact.length();
+            assert!(error < 0.01, "body {}: {} vs {}", i, approx, exact);
+        }
+
+        // No cell is approximated with `theta = 0`
+        for (i, exact) in system.barnes_hut_forces(0.0).iter().enumerate() {
+            assert!(exact.distance(system.force(i)) < 1e-9 * exact.length());
+        }
+    }
+
+    #[test]
+    fn test_ring() {
+        let system = NBody::ring(5, 2.0, 0.1, 1.0, 0.5);
+        assert_eq!(system.bodies.len(), 5);
+        assert_eq!(system.bodies[0].position, DVec2::new(2.0, 0.0));
+
+        for body in &system.bodies {
+            assert!((body.position.length() - 2.0).abs() < 1e-12);
+            assert!((body.velocity.length() - 0.5).abs() < 1e-12);
+            assert!(body.position.dot(body.velocity).abs() < 1e-12);
+            assert!(body.position.perp_dot(body.velocity) > 0.0);
+            assert_eq!(body.mass, 0.1);
+        }
+
+        // Evenly spaced: the bodies balance around the origin
+        let center = system.iter().map(|body| body.position).sum::<DVec2>();
+        assert!(center.length() < 1e-12);
+    }
+
+    #[test]
+    fn test_spatial_bounds() {
+        let mut system = three_bodies();
+        assert_eq!(
+            system.spatial_bounds(),
+            Some((DVec2::new(-1.0, 0.0), DVec2::new(1.0, 1.0)))
+        );
+
+        // Escaped bodies don't stretch the box
+        system.bodies[1].position = DVec2::NAN;
+        assert_eq!(system.spatial_bounds(), Some((DVec2::NEG_X, DVec2::X)));
+
+        let empty = NBody::new(1.0, Vec::new(), NBodyColorSchema::FirstBodyVelToGB);
+        assert_eq!(empty.spatial_bounds(), None);
+    }
+
+    #[test]
+    fn test_mismatched_body_counts() {
+        let three = three_bodies();
+        let mut two = three_bodies();
+        two.bodies.pop();
+        two.bodies[0].velocity = DVec2::new(2.0, 0.0);
+
+        let lerped = three.lerp(&two, 0.5);
+        assert_eq!(lerped.bodies.len(), 3);
+        assert_eq!(lerped.bodies[0].velocity, DVec2::new(1.0, 0.0));
+        assert_eq!(lerped.bodies[2].position, three.bodies[2].position);
+        assert_eq!(two.lerp(&three, 0.5).bodies.len(), 2);
+
+        // Only the shared bodies are averaged
+        assert_eq!(three.distance(&two), 1.0);
+        assert_eq!(two.distance(&three), 1.0);
+        let empty = NBody::new(1.0, Vec::new(), NBodyColorSchema::FirstBodyVelToGB);
+        assert_eq!(three.distance(&empty), 0.0);
+    }
+
+    // Integrators run against the analytic circular two-body orbit, documenting the trade-offs of
+    // `NBody::update` against reference ones. All of them use `NBody::force`.
+
+    /// Two bodies of mass `0.5` a unit apart, circling their center of mass with unit angular
+    /// velocity.
+    fn circular_orbit() -> NBody {
+        NBody::new(
+            1.0,
+            vec![
+                Body::new(0.5, DVec2::new(0.5, 0.0), DVec2::new(0.0, 0.5)),
+                Body::new(0.5, DVec2::new(-0.5, 0.0), DVec2::new(0.0, -0.5)),
+            ],
+            NBodyColorSchema::VelocityToRgb { v0: 1.0 },
+        )
+    }
+
+    fn analytic_positions(time: f64) -> [DVec2; 2] {
+        let position = DVec2::from_angle(time) * 0.5;
+        [position, -position]
+    }
+
+    fn accelerations(system: &NBody, bodies: &[Body]) -> Vec<DVec2> {
+        let system = NBody {
+            bodies: bodies.to_vec(),
+            ..system.clone()
+        };
+        (0..bodies.len())
+            .map(|i| system.force(i) / bodies[i].mass)
+            .collect()
+    }
+
+    fn energy(system: &NBody, bodies: &[Body]) -> f64 {
+        let kinetic = bodies
+            .iter()
+            .map(|body| 0.5 * body.mass * body.velocity.length_squared())
+            .sum::<f64>();
+        let distance = bodies[0].position.distance(bodies[1].position);
+        kinetic - system.g * bodies[0].mass * bodies[1].mass / distance
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    enum Integrator {
+        /// [`ChaoticSystem::update`]: semi-implicit Euler, moving the bodies one after another.
+        Update,
+        Euler,
+        Rk4,
+        Verlet,
+    }
+
+    impl Integrator {
+        fn step(self, system: &NBody, bodies: &mut [Body], dt: f64) {
+            match self {
+                Integrator::Update => {
+                    let mut stepped = system.clone();
+                    stepped.bodies = bodies.to_vec();
+                    stepped.update(dt);
+                    bodies.clone_from_slice(&stepped.bodies);
+                }
+                Integrator::Euler => {
+                    let accelerations = accelerations(system, bodies);
+                    for (body, acceleration) in bodies.iter_mut().zip(accelerations) {
+                        body.position += body.velocity * dt;
+                        body.velocity += acceleration * dt;
+                    }
+                }
+                Integrator::Rk4 => {
+                    // Derivative `(velocity, acceleration)` of every body at `bodies + k * h`
+                    let derivative = |k: &[(DVec2, DVec2)], h: f64| {
+                        let mut moved = bodies.to_vec();
+                        for (body, &(velocity, acceleration)) in moved.iter_mut().zip(k) {
+                            body.position += velocity * h;
+                            body.velocity += acceleration * h;
+                        }
+                        let velocities = moved.iter().map(|body| body.velocity);
+                        velocities
+                            .zip(accelerations(system, &moved))
+                            .collect::<Vec<_>>()
+                    };
+
+                    let zero = vec![(DVec2::ZERO, DVec2::ZERO); bodies.len()];
+                    let k1 = derivative(&zero, 0.0);
+                    let k2 = derivative(&k1, dt / 2.0);
+                    let k3 = derivative(&k2, dt / 2.0);
+                    let k4 = derivative(&k3, dt);
+                    for (i, body) in bodies.iter_mut().enumerate() {
+                        let weighted = |part: fn(&(DVec2, DVec2)) -> DVec2| {
+                            (part(&k1[i]) + 2.0 * part(&k2[i]) + 2.0 * part(&k3[i]) + part(&k4[i]))
+                                * dt
+                                / 6.0
+                        };
+                        body.position += weighted(|k| k.0);
+                        body.velocity += weighted(|k| k.1);
+                    }
+                }
+                Integrator::Verlet => {
+                    let start = accelerations(system, bodies);
+                    for (body, acceleration) in bodies.iter_mut().zip(start) {
+                        body.velocity += acceleration * dt / 2.0;
+                        body.position += body.velocity * dt;
+                    }
+                    let end = accelerations(system, bodies);
+                    for (body, acceleration) in bodies.iter_mut().zip(end) {
+                        body.velocity += acceleration * dt / 2.0;
+                    }
+                }
+            }
+        }
+
+        /// Largest distance from the analytic orbit and relative energy drift after `steps`.
+        fn errors(self, steps: usize, dt: f64) -> (f64, f64) {
+            let system = circular_orbit();
+            let mut bodies = system.bodies.clone();
+            let initial_energy = energy(&system, &bodies);
+
+            for _ in 0..steps {
+                self.step(&system, &mut bodies, dt);
+            }
+
+            let expected = analytic_positions(steps as f64 * dt);
+            let position_error = bodies
+                .iter()
+                .zip(expected)
+                .map(|(body, expected)| body.position.distance(expected))
+                .fold(0.0, f64::max);
+            let energy_drift = ((energy(&system, &bodies) - initial_energy) / initial_energy).abs();
+            (position_error, energy_drift)
+        }
+    }
+
+    const INTEGRATORS: [Integrator; 4] = [
+        Integrator::Update,
+        Integrator::Euler,
+        Integrator::Rk4,
+        Integrator::Verlet,
+    ];
+
+    #[test]
+    fn test_short_term_position_error() {
+        let [update, euler, rk4, verlet] =
+            INTEGRATORS.map(|integrator| integrator.errors(100, 0.01).0);
+
+        assert!(rk4 < 1e-9, "{}", rk4);
+        assert!(verlet < 1e-4, "{}", verlet);
+        assert!(euler > 1e-3, "{}", euler);
+        assert!(rk4 < verlet && verlet < euler);
+        // `update` is first order as well, only slightly ahead of explicit Euler
+        assert!(verlet < update && update < euler, "{}", update);
+    }
+
+    #[test]
+    fn test_long_term_energy_drift() {
+        // About 160 orbits with a coarse step
+        let [update, euler, rk4, verlet] =
+            INTEGRATORS.map(|integrator| integrator.errors(10000, 0.1).1);
 
-        total_distance / 3.0 // Average distance
+        assert!(verlet < 1e-6, "{}", verlet);
+        assert!(rk4 < 1e-3, "{}", rk4);
+        assert!(euler > 0.5, "{}", euler);
+        assert!(verlet < rk4 && rk4 < euler);
+        // Moving the bodies one after another does not conserve energy with a coarse step
+        assert!(update > 0.5, "{}", update);
     }
 }
@@ -0,0 +1,121 @@
+use crate::*;
+use bevy::color::Color;
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
+
+const INITIAL_POSITION: DVec2 = DVec2::new(-0.72, -0.64);
+
+/// Tinkerbell map.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tinkerbell {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub position: DVec2,
+}
+
+impl Tinkerbell {
+    pub fn new(a: f64, b: f64, c: f64, d: f64) -> Self {
+        Tinkerbell {
+            a,
+            b,
+            c,
+            d,
+            position: INITIAL_POSITION,
+        }
+    }
+
+    /// Parameters of the well-known chaotic attractor.
+    pub fn canonical() -> Self {
+        Self::new(0.9, -0.6013, 2.0, 0.5)
+    }
+}
+
+impl ChaoticSystem for Tinkerbell {
+    fn name() -> &'static str {
+        "Tinkerbell"
+    }
+
+    fn suggested_dt() -> f64 {
+        1.0
+    }
+
+    fn param_count(&self) -> usize {
+        2
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["a".to_string(), "b".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.a += pos.first().copied().unwrap_or_default();
+        self.b += pos.get(1).copied().unwrap_or_default();
+    }
+
+    fn reset_state(&mut self) {
+        self.position = INITIAL_POSITION;
+    }
+
+    fn update(&mut self, _dt: f64) {
+        let DVec2 { x, y } = self.position;
+
+        self.position = DVec2::new(
+            x * x - y * y + self.a * x + self.b * y,
+            2.0 * x * y + self.c * x + self.d * y,
+        );
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Tinkerbell {
+            a: lerp_f64(self.a, other.a, t),
+            b: lerp_f64(self.b, other.b, t),
+            c: lerp_f64(self.c, other.c, t),
+            d: lerp_f64(self.d, other.d, t),
+            position: self.position.lerp(other.position, t),
+        }
+    }
+
+    fn color(&self) -> Color {
+        position_to_color(self.position, DVec2::new(-0.4, -0.5), 1.0)
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.position.distance(other.position)
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.position.x, self.position.y]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_bounded() {
+        let mut system = Tinkerbell::canonical();
+
+        for _ in 0..5000 {
+            system.update(1.0);
+            assert!(system.position.is_finite());
+            assert!(system.position.length() < 3.0, "{:?}", system.position);
+        }
+    }
+
+    #[test]
+    fn test_reset_state() {
+        let mut system = Tinkerbell::canonical();
+        system.mutate(&[0.01, 0.02]);
+        let expected = system.clone();
+        for _ in 0..10 {
+            system.update(1.0);
+        }
+
+        system.reset_state();
+        assert_eq!(system.position, expected.position);
+        assert_eq!((system.a, system.b), (expected.a, expected.b));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::*;
+use bevy::color::Color;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Decays towards zero and counts how many times it was updated.
+#[derive(Clone)]
+pub struct Decay {
+    pub value: f64,
+    pub updates: usize,
+    /// Calls of [`ChaoticSystem::color`], shared between clones.
+    pub color_calls: Arc<AtomicUsize>,
+}
+
+impl Decay {
+    pub fn new(value: f64) -> Self {
+        Decay {
+            value,
+            updates: 0,
+            color_calls: Arc::default(),
+        }
+    }
+}
+
+impl ChaoticSystem for Decay {
+    fn name() -> &'static str {
+        "Decay"
+    }
+
+    fn suggested_dt() -> f64 {
+        0.1
+    }
+
+    fn param_count(&self) -> usize {
+        1
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["value".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.value += pos.first().copied().unwrap_or_default();
+    }
+
+    fn update(&mut self, dt: f64) {
+        self.value -= self.value * dt;
+        self.updates += 1;
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Decay {
+            value: lerp_f64(self.value, other.value, t),
+            updates: self.updates,
+            color_calls: self.color_calls.clone(),
+        }
+    }
+
+    fn color(&self) -> Color {
+        self.color_calls.fetch_add(1, Ordering::Relaxed);
+        Color::WHITE
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        (self.value - other.value).abs()
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.value]
+    }
+}
+
+/// Relaxes towards `cos(time)`, keeping `time` through [`ChaoticSystem::advance_time`].
+#[derive(Clone)]
+pub struct Driven {
+    pub value: f64,
+    pub time: f64,
+}
+
+impl Driven {
+    pub fn new(value: f64) -> Self {
+        Driven { value, time: 0.0 }
+    }
+}
+
+impl ChaoticSystem for Driven {
+    fn name() -> &'static str {
+        "Driven"
+    }
+
+    fn suggested_dt() -> f64 {
+        0.1
+    }
+
+    fn param_count(&self) -> usize {
+        1
+    }
+
+    fn param_labels(&self) -> Vec<String> {
+        vec!["value".to_string()]
+    }
+
+    fn mutate(&mut self, pos: &[f64]) {
+        self.value += pos.first().copied().unwrap_or_default();
+    }
+
+    fn update(&mut self, dt: f64) {
+        self.value += (self.time.cos() - self.value) * dt;
+    }
+
+    fn advance_time(&mut self, dt: f64) {
+        self.time += dt;
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Driven {
+            value: lerp_f64(self.value, other.value, t),
+            time: self.time,
+        }
+    }
+
+    fn color(&self) -> Color {
+        Color::WHITE
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        (self.value - other.value).abs()
+    }
+
+    fn state_vector(&self) -> Vec<f64> {
+        vec![self.value]
+    }
+}
@@ -1,3 +1,6 @@
+use bevy::color::{Color, Hsva, Mix, Oklaba};
+use bevy::math::{DVec2, DVec3};
+
 /// Convert angle to a normalized value between 0 and 1
 pub fn normalize_angle(angle: f64) -> f64 {
     let normalized = angle % (2.0 * std::f64::consts::PI);
@@ -11,3 +14,73 @@ pub fn normalize_angle(angle: f64) -> f64 {
 pub fn lerp_f64(a: f64, b: f64, t: f64) -> f64 {
     a + (b - a) * t
 }
+
+/// Blends `a` towards `b` by `t` in Oklab, a perceptually uniform space: midtones keep the
+/// lightness of the endpoints instead of turning muddy like in RGB.
+pub fn mix_oklab(a: Color, b: Color, t: f64) -> Color {
+    Oklaba::from(a).mix(&Oklaba::from(b), t as f32).into()
+}
+
+/// Colors a 2D position: hue from the angle around `center`, value from the distance to it
+/// (softly normalized by `radius`).
+pub fn position_to_color(position: DVec2, center: DVec2, radius: f64) -> Color {
+    let offset = position - center;
+    if !offset.is_finite() {
+        return Color::BLACK;
+    }
+
+    let hue = normalize_angle(offset.y.atan2(offset.x));
+    let dist = offset.length() / radius;
+    let value = dist / (dist + 1.0);
+
+    Hsva::new((hue * 360.0) as f32, 0.9, (0.2 + 0.8 * value) as f32, 1.0).into()
+}
+
+/// Distance between `a` and `b` on a torus with the given `period` along each axis, going around
+/// the edges when that is shorter.
+pub fn toroidal_distance(a: DVec2, b: DVec2, period: DVec2) -> f64 {
+    let offset = (a - b).abs() % period;
+    offset.min(period - offset).length()
+}
+
+/// Advances `state` by `dt` along the flow `f` with a classic 4th order Runge-Kutta step.
+pub fn rk4_step(state: DVec3, dt: f64, f: impl Fn(DVec3) -> DVec3) -> DVec3 {
+    let k1 = f(state);
+    let k2 = f(state + k1 * (dt * 0.5));
+    let k3 = f(state + k2 * (dt * 0.5));
+    let k4 = f(state + k3 * dt);
+
+    state + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * (dt / 6.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::color::Srgba;
+
+    #[test]
+    fn test_mix_oklab_keeps_lightness() {
+        let (red, green) = (Color::srgb(1.0, 0.0, 0.0), Color::srgb(0.0, 1.0, 0.0));
+        let lightness = |color: Color| Oklaba::from(color).lightness;
+
+        let mixed = mix_oklab(red, green, 0.5);
+        let expected = (lightness(red) + lightness(green)) / 2.0;
+        assert!((lightness(mixed) - expected).abs() < 1e-3);
+
+        // Naive sRGB blending darkens the midpoint
+        let naive = Srgba::from(red).mix(&Srgba::from(green), 0.5);
+        assert!(lightness(mixed) > lightness(naive.into()) + 0.05);
+    }
+
+    #[test]
+    fn test_toroidal_distance_wraps() {
+        let period = DVec2::ONE;
+        let distance = toroidal_distance(DVec2::new(0.01, 0.5), DVec2::new(0.99, 0.5), period);
+        assert!((distance - 0.02).abs() < 1e-12, "{}", distance);
+
+        let distance = toroidal_distance(DVec2::new(0.2, 0.1), DVec2::new(0.5, 0.5), period);
+        assert!((distance - 0.5).abs() < 1e-12, "{}", distance);
+        // Points a whole period apart coincide
+        assert!(toroidal_distance(DVec2::splat(0.3), DVec2::splat(2.3), period) < 1e-12);
+    }
+}
@@ -1,11 +1,17 @@
+use crate::{LayerData, ViewerState};
 use bevy::input::mouse::MouseWheel;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_egui::EguiContexts;
+use chaotic::ChaoticSystem;
 
 const MAX_ZOOM_IN: f32 = 0.1;
 const MAX_ZOOM_OUT: f32 = 10.0;
 const ZOOM_SCALE_SPEED: f32 = 0.003;
+/// Offset of the camera from the point it looks at, along every axis.
+const CAMERA_DISTANCE: f32 = 10000.0;
+/// Free space left around the fitted grid, relative to its projected size.
+const FIT_MARGIN: f32 = 1.1;
 
 #[derive(Component, Default)]
 pub struct MainCamera {
@@ -63,6 +69,114 @@ pub fn camera_zoom(
     Ok(())
 }
 
+/// Direction the camera looks at the layer stack from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CameraView {
+    #[default]
+    Diagonal,
+    /// Straight down the Z axis, every layer covers the ones below it.
+    Top,
+    /// Like [`CameraView::Top`], but can't be rotated and shows only the latest layer, for clean
+    /// single-frame captures.
+    Flat,
+}
+
+impl CameraView {
+    /// Camera transform looking at the layer at height `z`.
+    pub fn transform(self, z: f32) -> Transform {
+        let target = Vec3::new(0.0, 0.0, z);
+        match self {
+            CameraView::Diagonal => grid_view(z),
+            CameraView::Top | CameraView::Flat => {
+                Transform::from_translation(target + Vec3::Z * CAMERA_DISTANCE)
+                    .looking_at(target, Vec3::Y)
+            }
+        }
+    }
+
+    /// Whether only the latest layer is shown.
+    pub fn is_flat(self) -> bool {
+        self == CameraView::Flat
+    }
+}
+
+/// Camera transform looking down at the layer at height `z`, from the default diagonal direction.
+pub fn grid_view(z: f32) -> Transform {
+    let target = Vec3::new(0.0, 0.0, z);
+    Transform::from_translation(target + Vec3::ONE * CAMERA_DISTANCE).looking_at(target, Vec3::Z)
+}
+
+/// Orthographic scale fitting a `grid_size` layer and `stack_height` of layers below it into the
+/// `viewport` (in logical pixels) when looking from `view`.
+pub fn fit_orthographic_scale(
+    grid_size: Vec2,
+    stack_height: f32,
+    view: &Transform,
+    viewport: Vec2,
+) -> f32 {
+    let (right, up) = (view.right(), view.up());
+    let half = grid_size / 2.0;
+
+    let mut min = Vec2::INFINITY;
+    let mut max = Vec2::NEG_INFINITY;
+    for x in [-half.x, half.x] {
+        for y in [-half.y, half.y] {
+            for z in [-stack_height, 0.0] {
+                let corner = Vec3::new(x, y, z);
+                let projected = vec2(corner.dot(*right), corner.dot(*up));
+                min = min.min(projected);
+                max = max.max(projected);
+            }
+        }
+    }
+
+    let fit = (max - min) / viewport.max(Vec2::ONE);
+    (fit.max_element() * FIT_MARGIN).clamp(MAX_ZOOM_IN, MAX_ZOOM_OUT)
+}
+
+/// Centers the camera on the top layer from [`LayerData::camera_view`] and zooms so the whole
+/// grid fits into the window, when [`LayerData::request_camera_fit`] is set or on `Home`.
+pub fn fit_camera_to_grid_sys<T: ChaoticSystem>(
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<ViewerState<T>>,
+    mut layer_data: ResMut<LayerData>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut camera: Query<(&mut Projection, &mut Transform), With<MainCamera>>,
+) -> Result<(), BevyError> {
+    if !layer_data.request_camera_fit && !keys.just_pressed(KeyCode::Home) {
+        return Ok(());
+    }
+    layer_data.request_camera_fit = false;
+
+    let (mut projection, mut transform) = camera.single_mut()?;
+    let Projection::Orthographic(ref mut projection) = *projection else {
+        error!("Expected orthographic projection");
+        return Ok(());
+    };
+
+    let window = window.single()?;
+    let viewport = vec2(window.resolution.width(), window.resolution.height());
+    let (width, height) = layer_data
+        .render
+        .layout
+        .image_size(&state.samples.dimensions);
+
+    let stack_height = if layer_data.camera_view.is_flat() {
+        0.0
+    } else {
+        layer_data.current_size()
+    };
+    *transform = layer_data.camera_view.transform(layer_data.current_size());
+    projection.scale = fit_orthographic_scale(
+        vec2(width as f32, height as f32),
+        stack_height,
+        &transform,
+        viewport,
+    );
+
+    Ok(())
+}
+
 pub fn camera_move_by_mouse(
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     mut cursor_moved_events: EventReader<CursorMoved>,
@@ -139,9 +253,10 @@ pub fn rotate_camera(
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     mut cursor_moved_events: EventReader<CursorMoved>,
     mut camera: Query<(&mut Transform, &mut MainCamera), With<MainCamera>>,
+    layer_data: Res<LayerData>,
     mut contexts: EguiContexts,
 ) -> Result<(), BevyError> {
-    if contexts.ctx_mut()?.is_pointer_over_area() {
+    if layer_data.camera_view.is_flat() || contexts.ctx_mut()?.is_pointer_over_area() {
         return Ok(());
     }
 
@@ -178,3 +293,110 @@ pub fn rotate_camera(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InitData;
+    use bevy::window::WindowResolution;
+    use chaotic::{Dimensions, Mandelbrot};
+
+    #[test]
+    fn test_fit_orthographic_scale() {
+        let grid = vec2(512.0, 512.0);
+        let view = grid_view(0.0);
+
+        // From the diagonal a square spans `2 * sqrt(2) * half` across and `2 * sqrt(2/3) * half`
+        // up the screen.
+        let across = 512.0 * 2.0f32.sqrt();
+        let up = 512.0 * (2.0f32 / 3.0).sqrt();
+
+        let wide = fit_orthographic_scale(grid, 0.0, &view, vec2(1000.0, 500.0));
+        assert!((wide - up / 500.0 * FIT_MARGIN).abs() < 1e-4, "{}", wide);
+
+        let tall = fit_orthographic_scale(grid, 0.0, &view, vec2(500.0, 1000.0));
+        assert!(
+            (tall - across / 500.0 * FIT_MARGIN).abs() < 1e-4,
+            "{}",
+            tall
+        );
+
+        let stacked = fit_orthographic_scale(grid, 100.0, &view, vec2(1000.0, 500.0));
+        assert!(stacked > wide);
+
+        // Layers below are hidden behind the top one when looking along Z
+        let top = CameraView::Top.transform(0.0);
+        let top_scale = fit_orthographic_scale(grid, 100.0, &top, vec2(1000.0, 500.0));
+        assert!(
+            (top_scale - 512.0 / 500.0 * FIT_MARGIN).abs() < 1e-4,
+            "{}",
+            top_scale
+        );
+    }
+
+    fn fit_app() -> App {
+        let init_data = InitData::<Mandelbrot> {
+            dimensions: Dimensions::new(vec![400, 200]),
+            ..default()
+        };
+
+        let mut app = App::new();
+        app.insert_resource(init_data.init())
+            .insert_resource(LayerData {
+                current_depth: 10,
+                ..default()
+            })
+            .init_resource::<ButtonInput<KeyCode>>()
+            .add_systems(Update, fit_camera_to_grid_sys::<Mandelbrot>);
+        app.world_mut().spawn((
+            Window {
+                resolution: WindowResolution::new(800.0, 800.0),
+                ..default()
+            },
+            PrimaryWindow,
+        ));
+        app.world_mut().spawn((
+            MainCamera::default(),
+            Transform::default(),
+            Projection::Orthographic(OrthographicProjection::default_3d()),
+        ));
+        app
+    }
+
+    fn switch_view(app: &mut App, view: CameraView) -> (Transform, f32) {
+        let mut layer_data = app.world_mut().resource_mut::<LayerData>();
+        layer_data.camera_view = view;
+        layer_data.request_camera_fit = true;
+        app.update();
+
+        let mut camera_q = app
+            .world_mut()
+            .query_filtered::<(&Transform, &Projection), With<MainCamera>>();
+        let (transform, projection) = camera_q.single(app.world()).unwrap();
+        let Projection::Orthographic(projection) = projection else {
+            panic!("Expected orthographic projection");
+        };
+        (*transform, projection.scale)
+    }
+
+    #[test]
+    fn test_camera_view_transition() {
+        let mut app = fit_app();
+        let z = app.world().resource::<LayerData>().current_size();
+
+        let (flat, flat_scale) = switch_view(&mut app, CameraView::Flat);
+        assert_eq!(flat, CameraView::Flat.transform(z));
+        assert_eq!(flat.translation, vec3(0.0, 0.0, z + CAMERA_DISTANCE));
+        assert!((*flat.forward() - Vec3::NEG_Z).length() < 1e-6);
+        assert!((*flat.up() - Vec3::Y).length() < 1e-6);
+        // The wider side of the grid fills the window
+        assert!((flat_scale - 400.0 / 800.0 * FIT_MARGIN).abs() < 1e-4);
+
+        let (diagonal, diagonal_scale) = switch_view(&mut app, CameraView::Diagonal);
+        assert_eq!(diagonal, grid_view(z));
+        assert!(diagonal_scale > flat_scale);
+
+        let (top, _) = switch_view(&mut app, CameraView::Top);
+        assert_eq!(top, flat);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::InitData;
+use bevy::prelude::*;
+use chaotic::Dimensions;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+
+pub const CLI_USAGE: &str = "Options:
+    --window-width <px>     Window width
+    --window-height <px>    Window height
+    --title <text>          Window title
+    --width <samples>       Grid width
+    --height <samples>      Grid height
+    --dt <value>            Simulation time step
+    --mutation <value>      Overall mutation scale
+    --load <path>           Continue a run from a snapshot
+    --system <path>         Initial system from a RON or JSON (`.json`) file";
+
+/// Command-line overrides, unset values keep the defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CliArgs {
+    pub window_width: Option<f32>,
+    pub window_height: Option<f32>,
+    pub title: Option<String>,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub dt: Option<f64>,
+    pub mutation: Option<f64>,
+    pub load: Option<String>,
+    /// File with the initial system, see [`read_system`].
+    pub system: Option<String>,
+}
+
+impl CliArgs {
+    /// Parses arguments, not including the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = CliArgs::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+
+            match arg.as_str() {
+                "--window-width" => parsed.window_width = Some(parse_value(&arg, value()?)?),
+                "--window-height" => parsed.window_height = Some(parse_value(&arg, value()?)?),
+                "--title" => parsed.title = Some(value()?),
+                "--width" => parsed.width = Some(parse_value(&arg, value()?)?),
+                "--height" => parsed.height = Some(parse_value(&arg, value()?)?),
+                "--dt" => parsed.dt = Some(parse_value(&arg, value()?)?),
+                "--mutation" => parsed.mutation = Some(parse_value(&arg, value()?)?),
+                "--load" => parsed.load = Some(value()?),
+                "--system" => parsed.system = Some(value()?),
+                _ => return Err(format!("Unknown argument {}", arg)),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn apply_to_init_data<T>(&self, init_data: &mut InitData<T>) {
+        if self.width.is_some() || self.height.is_some() {
+            init_data.dimensions = Dimensions::new(vec![
+                self.width.unwrap_or(init_data.dimensions[0]),
+                self.height.unwrap_or(init_data.dimensions[1]),
+            ]);
+        }
+        if let Some(dt) = self.dt {
+            init_data.dt = dt;
+        }
+        if let Some(mutation) = self.mutation {
+            init_data.all_scale = mutation;
+        }
+    }
+
+    pub fn apply_to_window(&self, window: &mut Window) {
+        if let Some(title) = &self.title {
+            window.title = title.clone();
+        }
+        let width = self.window_width.unwrap_or(window.resolution.width());
+        let height = self.window_height.unwrap_or(window.resolution.height());
+        window.resolution.set(width, height);
+    }
+}
+
+/// Reads a system from `path`, as JSON for `.json` files and as RON otherwise.
+pub fn read_system<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, String> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| format!("Can't read system file {}: {}", path.display(), err))?;
+
+    let system = if is_json(path) {
+        serde_json::from_str(&text).map_err(|err| err.to_string())
+    } else {
+        ron::from_str(&text).map_err(|err| err.to_string())
+    };
+    system.map_err(|err| format!("Invalid system file {}: {}", path.display(), err))
+}
+
+/// Writes a system to `path` in the format [`read_system`] expects.
+pub fn write_system<T: Serialize>(path: impl AsRef<Path>, system: &T) -> Result<(), String> {
+    let path = path.as_ref();
+    let text = if is_json(path) {
+        serde_json::to_string_pretty(system).map_err(|err| err.to_string())
+    } else {
+        ron::ser::to_string_pretty(system, default()).map_err(|err| err.to_string())
+    }?;
+    std::fs::write(path, text)
+        .map_err(|err| format!("Can't write system file {}: {}", path.display(), err))
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+fn parse_value<V: std::str::FromStr>(arg: &str, value: String) -> Result<V, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value {:?} for {}", value, arg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::DVec2;
+    use chaotic::{Body, ChaoticSystem, Mandelbrot, NBody, NBodyColorSchema};
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        CliArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_defaults() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(args, CliArgs::default());
+
+        let mut init_data = InitData::<Mandelbrot>::default();
+        args.apply_to_init_data(&mut init_data);
+        assert_eq!(init_data.dimensions.sizes(), &[512, 512]);
+        assert_eq!(init_data.dt, Mandelbrot::suggested_dt());
+        assert_eq!(init_data.all_scale, 0.01);
+    }
+
+    #[test]
+    fn test_overrides() {
+        let args = parse(&[
+            "--width",
+            "64",
+            "--dt",
+            "0.5",
+            "--mutation",
+            "0.002",
+            "--title",
+            "Zoom",
+            "--window-width",
+            "800",
+            "--load",
+            "run.ron",
+            "--system",
+            "bodies.json",
+        ])
+        .unwrap();
+
+        let mut init_data = InitData::<Mandelbrot>::default();
+        args.apply_to_init_data(&mut init_data);
+        assert_eq!(init_data.dimensions.sizes(), &[64, 512]);
+        assert_eq!(init_data.dt, 0.5);
+        assert_eq!(init_data.all_scale, 0.002);
+        assert_eq!(args.load.as_deref(), Some("run.ron"));
+        assert_eq!(args.system.as_deref(), Some("bodies.json"));
+
+        let mut window = Window::default();
+        let default_height = window.resolution.height();
+        args.apply_to_window(&mut window);
+        assert_eq!(window.title, "Zoom");
+        assert_eq!(window.resolution.width(), 800.0);
+        assert_eq!(window.resolution.height(), default_height);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(parse(&["--width"]).is_err());
+        assert!(parse(&["--width", "wide"]).is_err());
+        assert!(parse(&["--depth", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_system_file_round_trip() {
+        let system = NBody::new(
+            0.5,
+            vec![
+                Body::new(1.0, DVec2::new(0.5, -1.0), DVec2::new(0.0, 0.25)),
+                Body::new(2.0, DVec2::new(-0.5, 1.0), DVec2::ZERO),
+            ],
+            NBodyColorSchema::FirstBodyVelToGB,
+        );
+        let dir = std::env::temp_dir().join(format!("chaotic-cli-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for name in ["bodies.ron", "bodies.json"] {
+            let path = dir.join(name);
+            write_system(&path, &system).unwrap();
+            let loaded: NBody = read_system(&path).unwrap();
+            assert_eq!(format!("{:?}", loaded), format!("{:?}", system), "{}", name);
+        }
+
+        std::fs::write(dir.join("broken.json"), "{ \"g\": 1.0 ").unwrap();
+        let err = read_system::<NBody>(dir.join("broken.json")).unwrap_err();
+        assert!(err.starts_with("Invalid system file"), "{}", err);
+        let err = read_system::<NBody>(dir.join("missing.ron")).unwrap_err();
+        assert!(err.starts_with("Can't read system file"), "{}", err);
+
+        // Written before the color alpha falloff was configurable
+        let old = "(g: 1.0, bodies: [], color_schema: FirstBodyVelToGB)";
+        std::fs::write(dir.join("old.ron"), old).unwrap();
+        let loaded = read_system::<NBody>(dir.join("old.ron")).unwrap();
+        assert_eq!(loaded.dist_scale, 1.0);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,381 @@
+use crate::Layer;
+use bevy::prelude::*;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Resource)]
+pub struct GifExport {
+    pub path: String,
+    /// Delay between frames in hundredths of a second.
+    pub frame_delay: u16,
+    pub request_export: bool,
+}
+
+impl Default for GifExport {
+    fn default() -> Self {
+        Self {
+            path: "layers.gif".to_string(),
+            frame_delay: 4,
+            request_export: false,
+        }
+    }
+}
+
+/// Encodes RGBA8 frames of the same size as a looping animated GIF.
+pub fn encode_gif<W: Write>(
+    writer: W,
+    frames: &[Vec<u8>],
+    width: u16,
+    height: u16,
+    frame_delay: u16,
+) -> Result<(), gif::EncodingError> {
+    let mut encoder = gif::Encoder::new(writer, width, height, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+
+    for rgba in frames {
+        let mut rgba = rgba.clone();
+        let mut frame = gif::Frame::from_rgba_speed(width, height, &mut rgba, 10);
+        frame.delay = frame_delay;
+        encoder.write_frame(&frame)?;
+    }
+
+    Ok(())
+}
+
+/// Writes RGBA8 frames of `size` to a GIF file at `path`, see [`encode_gif`]. Fails without
+/// creating the file if `size` doesn't fit into the 16 bit GIF dimensions.
+pub fn write_gif(
+    path: &str,
+    frames: &[Vec<u8>],
+    size: UVec2,
+    frame_delay: u16,
+) -> Result<(), BevyError> {
+    let (Ok(width), Ok(height)) = (u16::try_from(size.x), u16::try_from(size.y)) else {
+        return Err(format!("{}x{} frames are too large for a GIF", size.x, size.y).into());
+    };
+
+    encode_gif(File::create(path)?, frames, width, height, frame_delay)?;
+    Ok(())
+}
+
+/// Scales the color of every RGBA8 pixel by its alpha, for consumers expecting premultiplied alpha.
+pub fn premultiply_alpha(rgba: &mut [u8]) {
+    for pixel in rgba.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u16;
+        for channel in &mut pixel[..3] {
+            *channel = ((*channel as u16 * alpha + 127) / 255) as u8;
+        }
+    }
+}
+
+/// Writes the current layer stack to [`GifExport::path`], one frame per layer from the bottom up.
+pub fn export_gif_sys(
+    mut export: ResMut<GifExport>,
+    images: Res<Assets<Image>>,
+    layers_q: Query<(&Layer, &Sprite)>,
+) -> Result<(), BevyError> {
+    if !export.request_export {
+        return Ok(());
+    }
+    export.request_export = false;
+
+    let mut layers = layers_q.iter().collect::<Vec<_>>();
+    layers.sort_by_key(|(layer, _)| layer.depth);
+
+    let mut size = UVec2::ZERO;
+    let mut frames = Vec::with_capacity(layers.len());
+    for (_, sprite) in layers {
+        let Some(image) = images.get(&sprite.image) else {
+            continue;
+        };
+        let Some(data) = &image.data else {
+            continue;
+        };
+        size = image.size();
+        frames.push(data.clone());
+    }
+
+    if frames.is_empty() {
+        warn!("No layers to export");
+        return Ok(());
+    }
+
+    match write_gif(&export.path, &frames, size, export.frame_delay) {
+        Ok(()) => info!("Exported {} layers to {}", frames.len(), export.path),
+        Err(err) => error!("Failed to export layers to {}: {err}", export.path),
+    }
+
+    Ok(())
+}
+
+/// Output of [`LayerRecorder`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// `layer_00000.png`, `layer_00001.png`, ... in the [`LayerRecorder::path`] directory.
+    #[default]
+    PngSequence,
+    /// Animated GIF at [`LayerRecorder::path`], with the frame delay of [`GifExport`].
+    Gif,
+}
+
+/// Captures the layers spawned while recording is on, written out once it stops. Unlike
+/// [`GifExport`] this keeps only a chosen segment of the run, even after its layers were reset.
+#[derive(Resource)]
+pub struct LayerRecorder {
+    pub path: String,
+    pub format: RecordingFormat,
+    pub recording: bool,
+    /// Start or stop recording on the next update, stopping writes the frames.
+    pub request_toggle: bool,
+    /// Write the recorded frames again on the next update, e.g. after a failed save.
+    pub request_save: bool,
+    /// RGBA8 buffers of the recorded layers, oldest first.
+    pub frames: Vec<Vec<u8>>,
+    pub size: UVec2,
+    /// Write premultiplied instead of straight alpha, avoiding dark halos when the frames are
+    /// composited by tools expecting it.
+    pub premultiplied: bool,
+}
+
+impl Default for LayerRecorder {
+    fn default() -> Self {
+        Self {
+            path: "recording".to_string(),
+            format: RecordingFormat::default(),
+            recording: false,
+            request_toggle: false,
+            request_save: false,
+            frames: Vec::new(),
+            size: UVec2::ZERO,
+            premultiplied: false,
+        }
+    }
+}
+
+impl LayerRecorder {
+    /// Recorded frames as they are written, see [`Self::premultiplied`].
+    pub fn export_frames(&self) -> Cow<'_, [Vec<u8>]> {
+        if !self.premultiplied {
+            return Cow::Borrowed(&self.frames);
+        }
+        let mut frames = self.frames.clone();
+        for frame in &mut frames {
+            premultiply_alpha(frame);
+        }
+        Cow::Owned(frames)
+    }
+
+    /// Writes the recorded frames to [`Self::path`] in [`Self::format`].
+    pub fn save(&self, frame_delay: u16) -> Result<(), BevyError> {
+        let frames = self.export_frames();
+        match self.format {
+            RecordingFormat::PngSequence => {
+                let dir = Path::new(&self.path);
+                std::fs::create_dir_all(dir)?;
+                for (i, frame) in frames.iter().enumerate() {
+                    image::save_buffer(
+                        dir.join(format!("layer_{:05}.png", i)),
+                        frame,
+                        self.size.x,
+                        self.size.y,
+                        image::ExtendedColorType::Rgba8,
+                    )?;
+                }
+            }
+            RecordingFormat::Gif => write_gif(&self.path, &frames, self.size, frame_delay)?,
+        }
+        Ok(())
+    }
+}
+
+/// Toggles [`LayerRecorder`] with F9.
+pub fn recorder_hotkey_sys(keys: Res<ButtonInput<KeyCode>>, mut recorder: ResMut<LayerRecorder>) {
+    if keys.just_pressed(KeyCode::F9) {
+        recorder.request_toggle = true;
+    }
+}
+
+/// Starts and stops [`LayerRecorder`] and copies every newly spawned layer into it while
+/// recording.
+pub fn record_layers_sys(
+    mut recorder: ResMut<LayerRecorder>,
+    gif_export: Res<GifExport>,
+    images: Res<Assets<Image>>,
+    new_layers_q: Query<(&Layer, &Sprite), Added<Layer>>,
+) -> Result<(), BevyError> {
+    if recorder.request_toggle {
+        recorder.request_toggle = false;
+        recorder.recording = !recorder.recording;
+
+        if recorder.recording {
+            recorder.frames.clear();
+            info!("Recording layers");
+        } else {
+            recorder.request_save = true;
+        }
+    }
+
+    if recorder.request_save {
+        recorder.request_save = false;
+        if recorder.frames.is_empty() {
+            warn!("No layers recorded");
+        } else {
+            match recorder.save(gif_export.frame_delay) {
+                Ok(()) => info!(
+                    "Saved {} recorded layers to {}",
+                    recorder.frames.len(),
+                    recorder.path
+                ),
+                Err(err) => error!(
+                    "Failed to save recorded layers to {}, keeping them until the next recording: \
+                     {err}",
+                    recorder.path
+                ),
+            }
+        }
+    }
+
+    if !recorder.recording {
+        return Ok(());
+    }
+
+    let mut layers = new_layers_q.iter().collect::<Vec<_>>();
+    layers.sort_by_key(|(layer, _)| layer.depth);
+    for (_, sprite) in layers {
+        let Some(image) = images.get(&sprite.image) else {
+            continue;
+        };
+        let Some(data) = &image.data else {
+            continue;
+        };
+        recorder.size = image.size();
+        recorder.frames.push(data.clone());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_image, build_rgba, RenderSettings};
+    use chaotic::{Dimensions, Mandelbrot, MandelbrotColorSchema, Samples};
+
+    #[test]
+    fn test_encode_two_frames() {
+        let mut samples = Samples::new(
+            Mandelbrot::new(MandelbrotColorSchema::Distance),
+            Dimensions::new(vec![3, 2]),
+            &[0.5, 0.5],
+            1.0,
+        );
+        let first = build_rgba(&samples, &RenderSettings::default());
+        samples.update(3, 1.0);
+        let second = build_rgba(&samples, &RenderSettings::default());
+
+        let mut bytes = Vec::new();
+        encode_gif(&mut bytes, &[first, second], 3, 2, 7).unwrap();
+
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(bytes.as_slice())
+            .unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (3, 2));
+
+        let mut frame_count = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 7);
+            frame_count += 1;
+        }
+        assert_eq!(frame_count, 2);
+    }
+
+    #[test]
+    fn test_write_gif_rejects_oversized_frames() {
+        let path = std::env::temp_dir().join("chaotic_oversized.gif");
+        let path = path.to_str().unwrap();
+        let frames = vec![vec![0; 70_000 * 4]];
+
+        assert!(write_gif(path, &frames, UVec2::new(70_000, 1), 4).is_err());
+        assert!(!Path::new(path).exists());
+    }
+
+    #[test]
+    fn test_record_new_layers() {
+        let mut app = App::new();
+        app.init_resource::<Assets<Image>>()
+            .init_resource::<GifExport>()
+            .insert_resource(LayerRecorder {
+                recording: true,
+                ..default()
+            })
+            .add_systems(Update, record_layers_sys);
+
+        let (width, height) = (3u32, 2u32);
+        let len = (width * height * 4) as usize;
+        let spawn_layers = |app: &mut App, depths: std::ops::Range<usize>| {
+            for depth in depths {
+                let image = build_image(vec![depth as u8; len], width, height);
+                let handle = app.world_mut().resource_mut::<Assets<Image>>().add(image);
+                app.world_mut()
+                    .spawn((Layer { depth }, Sprite::from_image(handle)));
+            }
+        };
+
+        spawn_layers(&mut app, 0..3);
+        app.update();
+        spawn_layers(&mut app, 3..5);
+        app.update();
+        // Already recorded layers aren't added again
+        app.update();
+
+        let recorder = app.world().resource::<LayerRecorder>();
+        assert_eq!(recorder.frames.len(), 5);
+        assert_eq!(recorder.size, UVec2::new(3, 2));
+        for (depth, frame) in recorder.frames.iter().enumerate() {
+            assert_eq!(frame, &vec![depth as u8; len]);
+        }
+    }
+
+    #[test]
+    fn test_failed_save_keeps_frames() {
+        let mut app = App::new();
+        let blocker = std::env::temp_dir().join("chaotic_recording_blocker");
+        std::fs::write(&blocker, []).unwrap();
+        app.init_resource::<Assets<Image>>()
+            .init_resource::<GifExport>()
+            .insert_resource(LayerRecorder {
+                // A directory can't be created inside a file
+                path: blocker.join("recording").to_str().unwrap().to_string(),
+                recording: true,
+                request_toggle: true,
+                frames: vec![vec![0; 4]],
+                size: UVec2::ONE,
+                ..default()
+            })
+            .add_systems(Update, record_layers_sys);
+
+        app.update();
+
+        let recorder = app.world().resource::<LayerRecorder>();
+        assert!(!recorder.recording);
+        assert_eq!(recorder.frames.len(), 1);
+    }
+
+    #[test]
+    fn test_premultiplied_frames() {
+        let red = vec![255, 0, 0, 128];
+        let mut recorder = LayerRecorder {
+            frames: vec![red.clone()],
+            size: UVec2::ONE,
+            ..default()
+        };
+        assert_eq!(recorder.export_frames()[0], red);
+
+        recorder.premultiplied = true;
+        assert_eq!(recorder.export_frames()[0], vec![128, 0, 0, 128]);
+        // The recorded frames themselves stay straight
+        assert_eq!(recorder.frames[0], red);
+    }
+}
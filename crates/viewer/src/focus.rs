@@ -0,0 +1,150 @@
+use crate::{predict_orbits, OrbitPreview, SelectedSample, ViewerState};
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use chaotic::{ChaoticSystem, NBody};
+
+/// Selected [`NBody`] sample drawn on its own over the whole window, hiding the layer stack.
+#[derive(Resource)]
+pub struct FocusView {
+    pub enabled: bool,
+    /// Pixels left free around the bodies.
+    pub margin: f32,
+    /// Radius of every drawn body in pixels.
+    pub body_radius: f32,
+}
+
+impl Default for FocusView {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            margin: 32.0,
+            body_radius: 6.0,
+        }
+    }
+}
+
+/// Camera of the [`FocusView`]: `center` of the system space lands in the middle of the screen,
+/// `scale` is pixels per unit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FocusFrame {
+    pub center: DVec2,
+    pub scale: f64,
+}
+
+impl FocusFrame {
+    /// Frames `bounds` (see [`ChaoticSystem::spatial_bounds`]) in a screen of `size`, keeping
+    /// `margin` pixels on every side. A degenerate box is treated as a unit one.
+    pub fn fit((min, max): (DVec2, DVec2), size: Vec2, margin: f32) -> Self {
+        let extent = max - min;
+        let extent = DVec2::select(extent.cmpgt(DVec2::ZERO), extent, DVec2::ONE);
+        let free = (size - 2.0 * margin).max(Vec2::ONE).as_dvec2();
+        FocusFrame {
+            center: (min + max) / 2.0,
+            scale: (free / extent).min_element(),
+        }
+    }
+
+    /// Screen position of `position` in a screen of `size`, with `y` growing downwards.
+    pub fn to_screen(&self, position: DVec2, size: Vec2) -> Vec2 {
+        let offset = ((position - self.center) * self.scale).as_vec2();
+        size / 2.0 + vec2(offset.x, -offset.y)
+    }
+}
+
+/// Draws the bodies of the selected sample, with their [`predict_orbits`] if the orbit preview is
+/// enabled, framed by [`FocusFrame::fit`].
+pub fn focus_view_sys(
+    mut contexts: EguiContexts,
+    focus: Res<FocusView>,
+    preview: Res<OrbitPreview>,
+    state: Res<ViewerState<NBody>>,
+    selected: Res<SelectedSample>,
+) -> Result {
+    if !focus.enabled {
+        return Ok(());
+    }
+
+    let system = &state.samples.samples[selected.index(&state.samples.dimensions)];
+    let orbits = if preview.enabled {
+        predict_orbits(system, preview.horizon, state.dt)
+    } else {
+        Vec::new()
+    };
+
+    let ctx = contexts.ctx_mut()?;
+    egui::CentralPanel::default()
+        .frame(egui::Frame::new().fill(egui::Color32::BLACK))
+        .show(ctx, |ui| {
+            let rect = ui.max_rect();
+            let size = vec2(rect.width(), rect.height());
+            let bounds = orbits
+                .iter()
+                .flatten()
+                .filter(|position| position.is_finite())
+                .fold(system.spatial_bounds(), |bounds, &position| match bounds {
+                    None => Some((position, position)),
+                    Some((min, max)) => Some((min.min(position), max.max(position))),
+                });
+            let Some(bounds) = bounds else {
+                return;
+            };
+
+            let frame = FocusFrame::fit(bounds, size, focus.margin);
+            let point = |position: DVec2| {
+                let screen = frame.to_screen(position, size);
+                rect.min + egui::vec2(screen.x, screen.y)
+            };
+
+            let painter = ui.painter();
+            for (orbit, color) in orbits.iter().zip(system.body_colors()) {
+                let points = orbit
+                    .iter()
+                    .filter(|position| position.is_finite())
+                    .map(|&position| point(position))
+                    .collect();
+                painter.add(egui::Shape::line(points, (1.0, egui_color(color))));
+            }
+            for (body, color) in system.iter().zip(system.body_colors()) {
+                if body.position.is_finite() {
+                    painter.circle_filled(
+                        point(body.position),
+                        focus.body_radius,
+                        egui_color(color),
+                    );
+                }
+            }
+        });
+
+    Ok(())
+}
+
+fn egui_color(color: Color) -> egui::Color32 {
+    let [r, g, b, a] = color.to_srgba().to_u8_array();
+    egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_frame_to_screen() {
+        let bounds = (DVec2::new(-1.0, 0.0), DVec2::new(3.0, 1.0));
+        let size = vec2(200.0, 100.0);
+        let frame = FocusFrame::fit(bounds, size, 10.0);
+        assert_eq!(frame.center, DVec2::new(1.0, 0.5));
+        assert_eq!(frame.scale, 45.0);
+
+        assert_eq!(frame.to_screen(frame.center, size), vec2(100.0, 50.0));
+        // Limited by the width, with `y` flipped
+        assert_eq!(frame.to_screen(bounds.0, size), vec2(10.0, 72.5));
+        assert_eq!(frame.to_screen(bounds.1, size), vec2(190.0, 27.5));
+
+        // A single body sits in the middle
+        let point = DVec2::new(5.0, -2.0);
+        let frame = FocusFrame::fit((point, point), size, 10.0);
+        assert_eq!(frame.scale, 80.0);
+        assert_eq!(frame.to_screen(point, size), vec2(100.0, 50.0));
+    }
+}
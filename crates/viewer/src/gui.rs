@@ -1,29 +1,190 @@
-use crate::{InitData, LayerData};
+use crate::{
+    zoom_to_area_box,
+    zoom_to_box,
+    CameraView,
+    FocusView,
+    GifExport,
+    ImageLayout,
+    InitData,
+    LayerData,
+    LayerRecorder,
+    OrbitPreview,
+    RandomRestart,
+    RecordingFormat,
+    SampleHistory,
+    SelectedSample,
+    SnapshotFile,
+    StepEdge,
+    ViewerState,
+};
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
-use chaotic::ChaoticSystem;
+use chaotic::{sample_stats, AxisDirection, ChaoticSystem, GridOrigin, SampleStats};
+use egui_plot::{Line, Plot, PlotPoints};
+use std::time::Duration;
 
+/// Linear RGB of the background, as edited by the color picker.
+fn clear_color_rgb(clear_color: &ClearColor) -> [f32; 3] {
+    let color = clear_color.0.to_linear();
+    [color.red, color.green, color.blue]
+}
+
+fn set_clear_color(clear_color: &mut ClearColor, rgb: [f32; 3]) {
+    clear_color.0 = Color::linear_rgb(rgb[0], rgb[1], rgb[2]);
+}
+
+/// How often the statistics window recomputes its values.
+const STATS_REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+#[allow(clippy::too_many_arguments)]
 pub fn gui_system<T: ChaoticSystem + Clone>(
     mut contexts: EguiContexts,
+    state: Res<ViewerState<T>>,
     mut layer_data: ResMut<LayerData>,
     mut init_data: ResMut<InitData<T>>,
+    mut selected: ResMut<SelectedSample>,
+    mut history: ResMut<SampleHistory>,
+    mut gif_export: ResMut<GifExport>,
+    mut recorder: ResMut<LayerRecorder>,
+    mut snapshot_file: ResMut<SnapshotFile>,
+    mut orbit_preview: ResMut<OrbitPreview>,
+    mut focus_view: ResMut<FocusView>,
+    mut random_restart: ResMut<RandomRestart>,
+    mut clear_color: ResMut<ClearColor>,
 ) -> Result {
-    egui::Window::new("Control").show(contexts.ctx_mut()?, |ui| {
-        ui.label("Layers gap:");
-        ui.add(egui::DragValue::new(&mut layer_data.layers_gap).speed(0.01));
+    let ctx = contexts.ctx_mut()?;
+
+    egui::Window::new("Control").show(ctx, |ui| {
+        ui.label("Depth spacing:");
+        ui.add(egui::Slider::new(&mut layer_data.depth_spacing, 0.01..=16.0).logarithmic(true));
+
+        ui.horizontal(|ui| {
+            ui.label("Background:");
+            let mut rgb = clear_color_rgb(&clear_color);
+            if ui.color_edit_button_rgb(&mut rgb).changed() {
+                set_clear_color(&mut clear_color, rgb);
+            }
+        });
 
         ui.label("Target Depth:");
         ui.add(egui::DragValue::new(&mut layer_data.target_depth).speed(1));
         layer_data.target_depth = layer_data.target_depth.max(1);
 
+        ui.label("Memory budget (MiB):");
+        let mut budget_mib = layer_data.memory_budget >> 20;
+        ui.add(egui::DragValue::new(&mut budget_mib).speed(16));
+        layer_data.memory_budget = budget_mib.max(1) << 20;
+
         ui.label(format!("Current Depth: {}", layer_data.current_depth));
 
+        ui.label("Layers per batch:");
+        ui.add(egui::DragValue::new(&mut layer_data.layers_per_batch).speed(1));
+        layer_data.layers_per_batch = layer_data.layers_per_batch.max(1);
+
+        ui.label("Batches computed ahead:");
+        ui.add(egui::DragValue::new(&mut layer_data.prefetch_batches).speed(1));
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut layer_data.deterministic, "Fixed layers per frame");
+            if layer_data.deterministic {
+                ui.add(egui::DragValue::new(&mut layer_data.layers_per_frame).speed(1));
+                layer_data.layers_per_frame = layer_data.layers_per_frame.max(1);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let mut freeze = layer_data.convergence_tol.is_some();
+            ui.checkbox(&mut freeze, "Freeze converged");
+            match (freeze, layer_data.convergence_tol) {
+                (true, None) => layer_data.convergence_tol = Some(1e-9),
+                (false, Some(_)) => layer_data.convergence_tol = None,
+                _ => {}
+            }
+            if let Some(tol) = &mut layer_data.convergence_tol {
+                ui.add(egui::DragValue::new(tol).speed(1e-9));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Layout:");
+            let layout = &mut layer_data.render.layout;
+            ui.radio_value(layout, ImageLayout::RowMajor, "Row-major");
+            ui.radio_value(layout, ImageLayout::Transposed, "Transposed");
+        });
+        ui.checkbox(&mut layer_data.render.opaque, "Opaque layers");
+        ui.checkbox(&mut layer_data.render.system_alpha, "Fade stable samples");
+        ui.horizontal(|ui| {
+            ui.label("Color sub-steps:");
+            ui.add(egui::DragValue::new(&mut layer_data.render.temporal_frames).speed(1));
+            layer_data.render.temporal_frames = layer_data.render.temporal_frames.clamp(1, 64);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Fade-in updates:");
+            ui.add(egui::DragValue::new(&mut layer_data.render.fade_in_frames).speed(1));
+        });
+
+        ui.label("Gamma:");
+        ui.add(egui::Slider::new(&mut layer_data.render.gamma, 0.1..=4.0).logarithmic(true));
+        ui.label("Brightness:");
+        ui.add(egui::Slider::new(&mut layer_data.render.brightness, 0.1..=8.0).logarithmic(true));
+
+        ui.horizontal(|ui| {
+            ui.label("View:");
+            let view = layer_data.camera_view;
+            ui.radio_value(
+                &mut layer_data.camera_view,
+                CameraView::Diagonal,
+                "Diagonal",
+            );
+            ui.radio_value(&mut layer_data.camera_view, CameraView::Top, "Along Z");
+            ui.radio_value(&mut layer_data.camera_view, CameraView::Flat, "Flat 2D");
+            if layer_data.camera_view != view {
+                layer_data.request_camera_fit = true;
+            }
+        });
+
         ui.label("Width:");
         ui.add(egui::DragValue::new(&mut init_data.dimensions[0]).speed(1));
+        init_data.dimensions[0] = init_data.dimensions[0].max(1);
         ui.label("Height:");
         ui.add(egui::DragValue::new(&mut init_data.dimensions[1]).speed(1));
+        init_data.dimensions[1] = init_data.dimensions[1].max(1);
+
+        ui.horizontal(|ui| {
+            ui.label("Time step:");
+            ui.add(egui::DragValue::new(&mut init_data.dt).speed(0.001));
+            let suggested = T::suggested_dt();
+            let button = ui.button("Suggested");
+            if button.on_hover_text(suggested.to_string()).clicked() {
+                init_data.dt = suggested;
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Origin:");
+            let origin = &mut init_data.grid_origin;
+            ui.radio_value(origin, GridOrigin::Center, "Center");
+            ui.radio_value(origin, GridOrigin::Corner, "Corner");
+            if let GridOrigin::Custom(pos) = origin {
+                ui.label(format!("{:?}", pos));
+            }
+        });
+
+        let params = init_data.initial_sample.mutation_descriptor();
+        let label = |i: usize| {
+            params
+                .get(i)
+                .map_or_else(|| i.to_string(), |param| param.label.clone())
+        };
 
-        ui.label("Mutation Scale:");
+        ui.horizontal(|ui| {
+            ui.label("Mutation Scale:");
+            if ui.button("Suggested").clicked() {
+                for (i, scale) in init_data.mutation_scale.iter_mut().enumerate() {
+                    *scale = params.get(i).map_or(1.0, |param| param.scale);
+                }
+            }
+        });
 
         let mutation_min = 0.000000001;
         let mutation_max = 100000.0;
@@ -35,34 +196,247 @@ pub fn gui_system<T: ChaoticSystem + Clone>(
         for (i, scale) in init_data.mutation_scale.iter_mut().enumerate() {
             let speed = (*scale / 20.0).clamp(mutation_min, mutation_max);
             ui.horizontal(|ui| {
-                ui.label(format!("{}: ", i));
+                ui.label(format!("{}: ", label(i)));
                 ui.add(egui::DragValue::new(scale).speed(speed));
             });
             *scale = scale.clamp(mutation_min, mutation_max);
         }
 
+        ui.horizontal(|ui| {
+            ui.label("Aspect ratio:");
+            ui.add(egui::DragValue::new(&mut init_data.aspect_ratio).speed(0.01));
+            init_data.aspect_ratio = init_data.aspect_ratio.clamp(mutation_min, mutation_max);
+        });
+
         let init_data = &mut *init_data;
 
         ui.label("Initial mutation position:");
-        for (i, (mutation_offset, mutation_scale)) in init_data
-            .initial_mutation
+        let param_count = init_data.initial_mutation.len().max(params.len());
+        init_data.initial_mutation.resize(param_count, 0.0);
+        for (i, mutation_offset) in init_data.initial_mutation.iter_mut().enumerate() {
+            let mutation_scale = init_data
+                .mutation_scale
+                .get(i)
+                .or(params.get(i).map(|param| &param.scale))
+                .copied()
+                .unwrap_or(1.0);
+            let mut drag =
+                egui::DragValue::new(mutation_offset).speed(mutation_scale * init_data.all_scale);
+            if let Some((min, max)) = params.get(i).and_then(|param| param.range) {
+                drag = drag.range(min..=max);
+            }
+            ui.horizontal(|ui| {
+                ui.label(format!("{}: ", label(i)));
+                ui.add(drag);
+            });
+        }
+
+        ui.label("Axis offsets:");
+        let axes = init_data.dimensions.len();
+        init_data.axis_offsets.resize(axes, 0.0);
+        init_data
+            .axis_directions
+            .resize(axes, AxisDirection::Positive);
+        for (i, (offset, direction)) in init_data
+            .axis_offsets
             .iter_mut()
-            .zip(&init_data.mutation_scale)
+            .zip(&mut init_data.axis_directions)
             .enumerate()
         {
+            let speed =
+                init_data.mutation_scale.get(i).copied().unwrap_or(1.0) * init_data.all_scale;
             ui.horizontal(|ui| {
-                ui.label(format!("{}: ", i));
-                ui.add(
-                    egui::DragValue::new(mutation_offset)
-                        .speed(*mutation_scale * init_data.all_scale),
-                );
+                ui.label(format!("{}: ", label(i)));
+                ui.add(egui::DragValue::new(offset).speed(speed));
+                let mut negative = *direction == AxisDirection::Negative;
+                ui.checkbox(&mut negative, "Reversed");
+                *direction = if negative {
+                    AxisDirection::Negative
+                } else {
+                    AxisDirection::Positive
+                };
             });
         }
 
         if ui.button("Redraw").clicked() {
             layer_data.request_update = true;
         }
+
+        ui.horizontal(|ui| {
+            if ui.button("Randomize initial").clicked() {
+                init_data.initial_mutation = random_restart.roll(init_data.initial_mutation.len());
+                layer_data.request_update = true;
+            }
+            ui.add(egui::DragValue::new(&mut random_restart.min).speed(0.01));
+            ui.label("to");
+            ui.add(egui::DragValue::new(&mut random_restart.max).speed(0.01));
+            ui.label("seed");
+            ui.add(egui::DragValue::new(&mut random_restart.seed).speed(1));
+        });
+
+        ui.horizontal(|ui| {
+            // Around the selected sample, relative to the origin cell with Y pointing up
+            let dimensions = &state.samples.dimensions;
+            let pos = dimensions.index_to_pos(selected.index(dimensions));
+            let origin = init_data.grid_origin.cell(dimensions);
+            let center = vec2(
+                pos[0] as f32 - origin[0] as f32,
+                origin[1] as f32 - pos[1] as f32,
+            );
+
+            if ui.button("Zoom to box").clicked() {
+                zoom_to_area_box(&state, init_data);
+                layer_data.request_update = true;
+            }
+            for (text, factor) in [("Zoom in", 2.0), ("Zoom out", 0.5)] {
+                if ui.button(text).clicked() {
+                    zoom_to_box(&state, init_data, center, factor);
+                    layer_data.request_update = true;
+                }
+            }
+        });
+
+        ui.separator();
+        ui.label("GIF path:");
+        ui.text_edit_singleline(&mut gif_export.path);
+        ui.label("Frame delay (1/100 s):");
+        ui.add(egui::DragValue::new(&mut gif_export.frame_delay).speed(1));
+        if ui.button("Export GIF").clicked() {
+            gif_export.request_export = true;
+        }
+
+        ui.separator();
+        ui.label("Recording path:");
+        ui.text_edit_singleline(&mut recorder.path);
+        ui.horizontal(|ui| {
+            ui.radio_value(
+                &mut recorder.format,
+                RecordingFormat::PngSequence,
+                "PNG files",
+            );
+            ui.radio_value(&mut recorder.format, RecordingFormat::Gif, "GIF");
+        });
+        ui.checkbox(&mut recorder.premultiplied, "Premultiplied alpha");
+        let label = if recorder.recording {
+            format!("Stop recording (F9), {} layers", recorder.frames.len())
+        } else {
+            "Record (F9)".to_string()
+        };
+        if ui.button(label).clicked() {
+            recorder.request_toggle = true;
+        }
+        if !recorder.recording
+            && !recorder.frames.is_empty()
+            && ui.button("Save recording again").clicked()
+        {
+            recorder.request_save = true;
+        }
+
+        ui.separator();
+        ui.label("Snapshot path:");
+        ui.text_edit_singleline(&mut snapshot_file.path);
+        ui.horizontal(|ui| {
+            if ui.button("Save (F5)").clicked() {
+                snapshot_file.request_save = true;
+            }
+            if ui.button("Load").clicked() {
+                snapshot_file.request_load = true;
+            }
+        });
     });
 
+    egui::Window::new("Trajectory")
+        .default_open(false)
+        .show(ctx, |ui| {
+            ui.label(format!("Selected sample: {:?}", selected.pos));
+
+            ui.horizontal(|ui| {
+                ui.label("Arrow keys at grid ends:");
+                ui.radio_value(&mut selected.edge, StepEdge::Wrap, "Wrap");
+                ui.radio_value(&mut selected.edge, StepEdge::Clamp, "Clamp");
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("X axis:");
+                ui.add(egui::DragValue::new(&mut history.x_axis).speed(0.1));
+                ui.label("Y axis:");
+                ui.add(egui::DragValue::new(&mut history.y_axis).speed(0.1));
+            });
+
+            ui.label("History length:");
+            ui.add(egui::DragValue::new(&mut history.max_len).speed(1));
+            history.max_len = history.max_len.max(1);
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut orbit_preview.enabled, "Predicted orbits");
+                ui.add(egui::DragValue::new(&mut orbit_preview.horizon).speed(1));
+            });
+            ui.checkbox(&mut focus_view.enabled, "Focus view");
+
+            let points = PlotPoints::from(history.points());
+            Plot::new("trajectory_plot")
+                .view_aspect(1.0)
+                .data_aspect(1.0)
+                .show(ui, |plot_ui| plot_ui.line(Line::new("trajectory", points)));
+        });
+
+    Ok(())
+}
+
+/// Shows aggregate statistics of the current grid, recomputed every [`STATS_REFRESH_INTERVAL`].
+/// The metric is the length of every sample's state vector.
+pub fn stats_window_sys<T: ChaoticSystem>(
+    mut contexts: EguiContexts,
+    time: Res<Time>,
+    state: Res<ViewerState<T>>,
+    mut stats: Local<Option<SampleStats>>,
+    mut refresh: Local<Option<Timer>>,
+) -> Result {
+    let refresh =
+        refresh.get_or_insert_with(|| Timer::new(STATS_REFRESH_INTERVAL, TimerMode::Repeating));
+    if refresh.tick(time.delta()).just_finished() || stats.is_none() {
+        *stats = Some(sample_stats(&state.samples, |system| {
+            system
+                .state_vector()
+                .iter()
+                .map(|v| v * v)
+                .sum::<f64>()
+                .sqrt()
+        }));
+    }
+
+    let ctx = contexts.ctx_mut()?;
+    egui::Window::new("Statistics")
+        .default_open(false)
+        .show(ctx, |ui| {
+            let Some(stats) = *stats else {
+                return;
+            };
+            ui.label(format!("Samples: {}", state.samples.samples.len()));
+            ui.label(format!(
+                "State norm: min {:.4}, max {:.4}, mean {:.4}",
+                stats.min, stats.max, stats.mean
+            ));
+            ui.label(format!("Finite: {:.1}%", stats.finite_fraction * 100.0));
+            ui.label(format!("Escaped: {}", stats.escaped));
+        });
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_picker_sets_clear_color() {
+        let mut world = World::new();
+        world.insert_resource(ClearColor(Color::BLACK));
+        assert_eq!(clear_color_rgb(world.resource()), [0.0; 3]);
+
+        set_clear_color(&mut world.resource_mut(), [1.0, 0.5, 0.0]);
+        let clear_color = world.resource::<ClearColor>();
+        assert_eq!(clear_color.0, Color::linear_rgb(1.0, 0.5, 0.0));
+        assert_eq!(clear_color_rgb(clear_color), [1.0, 0.5, 0.0]);
+    }
+}
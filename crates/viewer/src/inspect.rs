@@ -0,0 +1,295 @@
+use crate::ViewerState;
+use bevy::prelude::*;
+use chaotic::{ChaoticSystem, Dimensions};
+use std::collections::VecDeque;
+
+/// What [`SelectedSample::step`] does at the first and last sample of the grid.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StepEdge {
+    /// The end of the grid wraps to its start and back.
+    #[default]
+    Wrap,
+    /// The selection stops at the first and last sample.
+    Clamp,
+}
+
+/// Sample of the grid currently inspected in the GUI.
+#[derive(Resource, Default)]
+pub struct SelectedSample {
+    /// Grid coordinates of the selected sample. Missing coordinates default to the grid center.
+    pub pos: Vec<usize>,
+    pub edge: StepEdge,
+}
+
+impl SelectedSample {
+    /// Linear index of the selected sample, clamped to the grid.
+    pub fn index(&self, dimensions: &Dimensions) -> usize {
+        let pos = dimensions
+            .sizes()
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| {
+                let coord = self.pos.get(i).copied().unwrap_or(size / 2);
+                coord.min(size.saturating_sub(1))
+            })
+            .collect::<Vec<_>>();
+
+        dimensions.pos_to_index(&pos)
+    }
+
+    /// Moves the selection by `delta` cells along `axis`. Stepping past the edge of an axis carries
+    /// into the next one like an odometer, the ends of the grid are handled by [`Self::edge`].
+    pub fn step(&mut self, dimensions: &Dimensions, axis: usize, delta: isize) {
+        let volume = dimensions.volume() as isize;
+        if volume == 0 {
+            return;
+        }
+
+        let stride = dimensions.strides()[axis] as isize;
+        let index = self.index(dimensions) as isize + delta * stride;
+        let index = match self.edge {
+            StepEdge::Wrap => index.rem_euclid(volume),
+            StepEdge::Clamp => index.clamp(0, volume - 1),
+        };
+        self.pos = dimensions.index_to_pos(index as usize);
+    }
+}
+
+/// Moves [`SelectedSample`] with the arrow keys (see [`SelectedSample::step`]) and logs the state
+/// of the newly selected sample. Up moves towards the top of the image, so to lower rows.
+pub fn select_sample_by_keys_sys<T: ChaoticSystem>(
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<ViewerState<T>>,
+    mut selected: ResMut<SelectedSample>,
+    mut history: ResMut<SampleHistory>,
+) {
+    let steps = [
+        (KeyCode::ArrowLeft, 0, -1),
+        (KeyCode::ArrowRight, 0, 1),
+        (KeyCode::ArrowUp, 1, -1),
+        (KeyCode::ArrowDown, 1, 1),
+    ];
+    let dimensions = &state.samples.dimensions;
+
+    let mut moved = false;
+    for (key, axis, delta) in steps {
+        if keys.just_pressed(key) && axis < dimensions.len() {
+            selected.step(dimensions, axis, delta);
+            moved = true;
+        }
+    }
+    if !moved {
+        return;
+    }
+
+    history.clear();
+    let index = selected.index(dimensions);
+    info!(
+        "Selected sample {:?}: {:?}",
+        selected.pos,
+        state.samples.samples[index].state_vector()
+    );
+}
+
+/// Recent states of the selected sample, oldest first.
+#[derive(Resource)]
+pub struct SampleHistory {
+    pub max_len: usize,
+    /// Index in the state vector plotted along X.
+    pub x_axis: usize,
+    /// Index in the state vector plotted along Y.
+    pub y_axis: usize,
+    states: VecDeque<Vec<f64>>,
+}
+
+impl Default for SampleHistory {
+    fn default() -> Self {
+        Self::new(512)
+    }
+}
+
+impl SampleHistory {
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            max_len,
+            x_axis: 0,
+            y_axis: 1,
+            states: VecDeque::with_capacity(max_len),
+        }
+    }
+
+    /// Records a new state, dropping the oldest ones if the history exceeds `max_len`.
+    pub fn push(&mut self, state: Vec<f64>) {
+        self.states.push_back(state);
+        while self.states.len() > self.max_len {
+            self.states.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.states.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Vec<f64>> {
+        self.states.iter()
+    }
+
+    /// Recorded states projected onto the `x_axis`/`y_axis` components.
+    pub fn points(&self) -> Vec<[f64; 2]> {
+        self.states
+            .iter()
+            .filter_map(|state| Some([*state.get(self.x_axis)?, *state.get(self.y_axis)?]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InitData;
+    use chaotic::Mandelbrot;
+
+    #[test]
+    fn test_history_caps_length() {
+        let mut history = SampleHistory::new(3);
+        for i in 0..5 {
+            history.push(vec![i as f64, -(i as f64), 0.5]);
+        }
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(
+            history.iter().cloned().collect::<Vec<_>>(),
+            vec![
+                vec![2.0, -2.0, 0.5],
+                vec![3.0, -3.0, 0.5],
+                vec![4.0, -4.0, 0.5]
+            ]
+        );
+        assert_eq!(
+            history.points(),
+            vec![[2.0, -2.0], [3.0, -3.0], [4.0, -4.0]]
+        );
+
+        history.x_axis = 2;
+        assert_eq!(history.points()[0], [0.5, -2.0]);
+
+        history.clear();
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_step_wraps_to_next_row() {
+        let dimensions = Dimensions::new(vec![3, 2]);
+        let mut selected = SelectedSample {
+            pos: vec![1, 0],
+            ..default()
+        };
+
+        selected.step(&dimensions, 0, 1);
+        assert_eq!(selected.pos, vec![2, 0]);
+        selected.step(&dimensions, 0, 1);
+        assert_eq!(selected.pos, vec![0, 1]);
+        selected.step(&dimensions, 0, -1);
+        assert_eq!(selected.pos, vec![2, 0]);
+
+        // The end of the grid wraps to the start
+        let mut selected = SelectedSample {
+            pos: vec![2, 1],
+            ..default()
+        };
+        selected.step(&dimensions, 0, 1);
+        assert_eq!(selected.pos, vec![0, 0]);
+        selected.step(&dimensions, 0, -1);
+        assert_eq!(selected.pos, vec![2, 1]);
+
+        selected.step(&dimensions, 1, 1);
+        assert_eq!(selected.pos, vec![2, 0]);
+        selected.step(&dimensions, 1, -1);
+        assert_eq!(selected.pos, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_step_clamps_at_grid_ends() {
+        let dimensions = Dimensions::new(vec![3, 2]);
+        let mut selected = SelectedSample {
+            pos: vec![2, 0],
+            edge: StepEdge::Clamp,
+        };
+
+        // Row ends still carry into the next row
+        selected.step(&dimensions, 0, 1);
+        assert_eq!(selected.pos, vec![0, 1]);
+        selected.step(&dimensions, 0, -1);
+        assert_eq!(selected.pos, vec![2, 0]);
+
+        selected.step(&dimensions, 1, 1);
+        assert_eq!(selected.pos, vec![2, 1]);
+        selected.step(&dimensions, 1, 1);
+        assert_eq!(selected.pos, vec![2, 1]);
+        selected.step(&dimensions, 0, 1);
+        assert_eq!(selected.pos, vec![2, 1]);
+
+        let mut selected = SelectedSample {
+            pos: vec![1, 0],
+            edge: StepEdge::Clamp,
+        };
+        selected.step(&dimensions, 1, -1);
+        assert_eq!(selected.pos, vec![0, 0]);
+        selected.step(&dimensions, 0, -1);
+        assert_eq!(selected.pos, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_select_sample_by_keys() {
+        let init_data = InitData::<Mandelbrot> {
+            dimensions: Dimensions::new(vec![3, 2]),
+            ..default()
+        };
+
+        let mut app = App::new();
+        app.insert_resource(init_data.init())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<SelectedSample>()
+            .init_resource::<SampleHistory>()
+            .add_systems(Update, select_sample_by_keys_sys::<Mandelbrot>);
+        app.world_mut()
+            .resource_mut::<SampleHistory>()
+            .push(vec![1.0, 2.0]);
+
+        // Starts at the center `[1, 1]`
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::ArrowRight);
+        app.update();
+        assert_eq!(app.world().resource::<SelectedSample>().pos, vec![2, 1]);
+        assert!(app.world().resource::<SampleHistory>().is_empty());
+    }
+
+    #[test]
+    fn test_selected_sample_index() {
+        let dimensions = Dimensions::new(vec![4, 6]);
+
+        let selected = SelectedSample::default();
+        assert_eq!(
+            selected.index(&dimensions),
+            dimensions.pos_to_index(&[2, 3])
+        );
+
+        let selected = SelectedSample {
+            pos: vec![10, 1],
+            ..default()
+        };
+        assert_eq!(
+            selected.index(&dimensions),
+            dimensions.pos_to_index(&[3, 1])
+        );
+    }
+}
@@ -1,46 +1,85 @@
-use crate::MainCamera;
+use crate::{CameraView, MainCamera, Producer, SampleHistory, SelectedSample};
 use bevy::asset::RenderAssetUsages;
-use bevy::math::DVec2;
 use bevy::prelude::*;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::tasks::futures_lite::future;
+use bevy::tasks::{block_on, AsyncComputeTaskPool, Task};
 use chaotic::{
-    Body,
+    AxisDirection,
     ChaoticSystem,
     Dimensions,
+    GridOrigin,
     Mandelbrot,
     MandelbrotColorSchema,
     NBody,
-    NBodyColorSchema,
     Samples,
 };
-use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::TryRecvError;
+use std::sync::{Arc, Mutex};
 
-#[derive(Resource)]
+#[derive(Resource, Serialize, Deserialize)]
 pub struct InitData<T> {
     pub mutation_scale: Vec<f64>,
     pub all_scale: f64,
     pub initial_mutation: Vec<f64>,
     pub dimensions: Dimensions,
+    /// Cell holding the unmutated `initial_sample`.
+    #[serde(default)]
+    pub grid_origin: GridOrigin,
+    /// Mutation added along every grid axis, pans the sampled region without moving the origin.
+    #[serde(default)]
+    pub axis_offsets: Vec<f64>,
+    /// Direction every grid axis sweeps its parameter in, missing ones are positive.
+    #[serde(default)]
+    pub axis_directions: Vec<AxisDirection>,
+    /// Width over height of the sampled region relative to the mutation scales: the second axis is
+    /// scaled by its inverse, so a square grid can cover a wide or tall parameter region.
+    #[serde(default = "default_aspect_ratio")]
+    pub aspect_ratio: f64,
 
     pub initial_sample: T,
     pub dt: f64,
     pub updates_per_iteration: usize,
 }
 
+fn default_aspect_ratio() -> f64 {
+    1.0
+}
+
+impl<T> InitData<T> {
+    /// Mutation between neighboring cells along every axis, before `all_scale`, with
+    /// [`Self::aspect_ratio`] applied.
+    pub fn axis_scales(&self) -> Vec<f64> {
+        let mut scales = self.mutation_scale.clone();
+        if let Some(scale) = scales.get_mut(1) {
+            *scale /= self.aspect_ratio;
+        }
+        scales
+    }
+}
+
 impl<T: ChaoticSystem + Clone> InitData<T> {
     pub fn init(&self) -> ViewerState<T> {
         let mut initial_sample = self.initial_sample.clone();
         initial_sample.mutate(&self.initial_mutation);
-        let samples = Samples::new(
+        let mutation_scale = self.axis_scales();
+        let mut samples = Samples::new_with_origin(
             initial_sample,
             self.dimensions.clone(),
-            &self.mutation_scale,
+            &mutation_scale,
+            &self.axis_offsets,
+            &self.axis_directions,
             self.all_scale,
+            &self.grid_origin,
         );
+        samples.enable_color_cache();
 
         ViewerState {
             initial_mutation: self.initial_mutation.clone(),
-            mutation_scale: self.mutation_scale.clone(),
+            mutation_scale,
+            axis_offsets: self.axis_offsets.clone(),
+            axis_directions: self.axis_directions.clone(),
             all_scale: self.all_scale,
             dt: self.dt,
             updates_per_iteration: self.updates_per_iteration,
@@ -52,42 +91,20 @@ impl<T: ChaoticSystem + Clone> InitData<T> {
 impl Default for InitData<NBody> {
     fn default() -> Self {
         // Build initial ThreeBody system (matching the original Chaos main)
-        let angle_a = 0.0;
-        let angle_b = std::f64::consts::PI * (1.0 / 3.0) * 2.0;
-        let angle_c = std::f64::consts::PI * (2.0 / 3.0) * 2.0;
-        let mass = 0.1;
-        let velocity = 0.0;
-
-        let initial_sample = NBody::new(
-            1.0,
-            vec![
-                Body::new(
-                    mass,
-                    rotate(DVec2::X, angle_a),
-                    rotate(DVec2::Y, angle_a) * velocity,
-                ),
-                Body::new(
-                    mass,
-                    rotate(DVec2::X, angle_b),
-                    rotate(DVec2::Y, angle_b) * velocity,
-                ),
-                Body::new(
-                    mass,
-                    rotate(DVec2::X, angle_c),
-                    rotate(DVec2::Y, angle_c) * velocity,
-                ),
-            ],
-            NBodyColorSchema::VelocityToRgb { v0: 1.0 },
-        );
+        let initial_sample = NBody::ring(3, 1.0, 0.1, 1.0, 0.0);
 
         Self {
-            dt: 0.33,
+            dt: NBody::suggested_dt(),
             updates_per_iteration: 1,
             initial_sample,
             mutation_scale: vec![1.0, 1.0],
             all_scale: 0.01,
             initial_mutation: vec![0.0, 0.0],
             dimensions: Dimensions::new_static(&[256, 256]),
+            grid_origin: GridOrigin::Center,
+            axis_offsets: Vec::new(),
+            axis_directions: Vec::new(),
+            aspect_ratio: 1.0,
         }
     }
 }
@@ -95,48 +112,125 @@ impl Default for InitData<NBody> {
 impl Default for InitData<Mandelbrot> {
     fn default() -> Self {
         Self {
-            dt: 0.01,
+            dt: Mandelbrot::suggested_dt(),
             updates_per_iteration: 1,
             initial_sample: Mandelbrot::new(MandelbrotColorSchema::Distance),
             mutation_scale: vec![1.0, 1.0],
             all_scale: 0.01,
             initial_mutation: vec![-0.8, 0.0],
             dimensions: Dimensions::new_static(&[512, 512]),
+            grid_origin: GridOrigin::Center,
+            axis_offsets: Vec::new(),
+            axis_directions: Vec::new(),
+            aspect_ratio: 1.0,
         }
     }
 }
 
+/// Bytes of a single RGBA8 layer.
+const BYTES_PER_PIXEL: usize = 4;
+
+/// Deepest layer stack of a `dimensions` grid fitting into `budget` bytes, at least one layer.
+pub fn max_depth_for_budget(dimensions: &Dimensions, budget: usize) -> usize {
+    let layer_bytes = dimensions.volume() * BYTES_PER_PIXEL;
+    (budget / layer_bytes.max(1)).max(1)
+}
+
 #[derive(Resource)]
 pub struct LayerData {
     pub target_depth: usize,
     pub current_depth: usize,
+    /// Bytes the layer images may take, caps `target_depth`, see [`max_depth_for_budget`].
+    pub memory_budget: usize,
 
-    pub layers_gap: f32,
+    /// Z distance between neighboring layers, stretches or compresses the stack.
+    pub depth_spacing: f32,
+    /// Number of layers computed by a single background task.
+    pub layers_per_batch: usize,
+    /// Compute exactly `layers_per_frame` layers on every tick on the main thread, instead of
+    /// spawning whenever a background batch finishes. Runs then take the same number of frames on
+    /// every machine.
+    pub deterministic: bool,
+    pub layers_per_frame: usize,
+    /// Compute up to this many batches ahead on a separate thread instead of one at a time, `0`
+    /// to disable. Settings are taken when the run starts, see [`LayerTask::producer`].
+    pub prefetch_batches: usize,
+    pub render: RenderSettings,
+    /// Stop updating samples that moved less than this during a layer, `None` to update all.
+    pub convergence_tol: Option<f64>,
 
     pub request_update: bool,
+    pub camera_view: CameraView,
+    /// Refit the camera to the grid, see [`crate::fit_camera_to_grid_sys`].
+    pub request_camera_fit: bool,
 }
 
 impl Default for LayerData {
     fn default() -> Self {
         Self {
-            layers_gap: 1.0,
+            depth_spacing: 1.0,
+            layers_per_batch: 8,
+            deterministic: false,
+            layers_per_frame: 1,
+            prefetch_batches: 0,
+            render: RenderSettings::default(),
+            convergence_tol: None,
             target_depth: 256,
             current_depth: 0,
+            memory_budget: 1 << 30,
             request_update: false,
+            camera_view: CameraView::default(),
+            request_camera_fit: true,
         }
     }
 }
 
 impl LayerData {
     pub fn current_size(&self) -> f32 {
-        self.current_depth as f32 * self.layers_gap
+        self.layer_z(self.current_depth)
+    }
+
+    /// Z coordinate of the layer at the given depth in the stack.
+    pub fn layer_z(&self, depth: usize) -> f32 {
+        depth as f32 * self.depth_spacing
+    }
+
+    /// Number of layers the next batch should compute, `0` once `target_depth` is reached.
+    pub fn next_batch_len(&self) -> usize {
+        let limit = if self.deterministic {
+            self.layers_per_frame
+        } else {
+            self.layers_per_batch
+        };
+        self.target_depth
+            .saturating_sub(self.current_depth)
+            .min(limit.max(1))
+    }
+
+    /// Lowers `target_depth` to what fits into `memory_budget`, warning if it had to.
+    pub fn apply_memory_budget(&mut self, dimensions: &Dimensions) {
+        let max_depth = max_depth_for_budget(dimensions, self.memory_budget);
+        if self.target_depth > max_depth {
+            warn!(
+                "Target depth {} exceeds the memory budget of {} MiB, limited to {}",
+                self.target_depth,
+                self.memory_budget >> 20,
+                max_depth
+            );
+            self.target_depth = max_depth;
+        }
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Serialize, Deserialize)]
 pub struct ViewerState<T> {
     pub initial_mutation: Vec<f64>,
     pub mutation_scale: Vec<f64>,
+    /// See [`InitData::axis_offsets`].
+    #[serde(default)]
+    pub axis_offsets: Vec<f64>,
+    #[serde(default)]
+    pub axis_directions: Vec<AxisDirection>,
     pub all_scale: f64,
     pub dt: f64,
     pub updates_per_iteration: usize,
@@ -144,98 +238,450 @@ pub struct ViewerState<T> {
 }
 
 #[derive(Component)]
-pub struct Layer;
+pub struct Layer {
+    /// Position of the layer in the stack, starting from `0`.
+    pub depth: usize,
+}
+
+/// Layers computed off the main thread, waiting to be spawned.
+pub struct LayerBatch<T> {
+    /// Samples state after the last layer of the batch.
+    pub samples: Samples<T>,
+    /// RGBA8 buffer of every computed layer.
+    pub layers: Vec<Vec<u8>>,
+    /// State vector of the selected sample after every computed layer.
+    pub selected_states: Vec<Vec<f64>>,
+    /// Width and height of the layers in the layout they were rendered with, which may have
+    /// changed since.
+    pub size: (u32, u32),
+}
+
+impl<T: ChaoticSystem + Clone> LayerBatch<T> {
+    /// Advances `samples` by `count` layers, rendering each of them into a buffer from `buffers`.
+    /// With `convergence_tol` converged samples are frozen and keep their cached colors.
+    #[allow(clippy::too_many_arguments)]
+    pub fn compute(
+        mut samples: Samples<T>,
+        count: usize,
+        updates_per_iteration: usize,
+        dt: f64,
+        convergence_tol: Option<f64>,
+        selected_index: usize,
+        render: RenderSettings,
+        buffers: &RgbaBufferPool,
+    ) -> Self {
+        let mut layers = Vec::with_capacity(count);
+        let mut selected_states = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            match convergence_tol {
+                Some(tol) => samples.update_active(updates_per_iteration, dt, tol),
+                None => samples.update(updates_per_iteration, dt),
+            }
+            samples.refresh_colors();
+            selected_states.push(samples.samples[selected_index].state_vector());
+            let buffer = buffers.take(samples.dimensions.volume() * BYTES_PER_PIXEL);
+            layers.push(if render.temporal_frames > 1 {
+                let layer_dt = updates_per_iteration as f64 * dt;
+                build_rgba_averaged(buffer, &samples, &render, layer_dt)
+            } else {
+                build_rgba_into(buffer, &samples, &render)
+            });
+        }
+
+        LayerBatch {
+            size: render.layout.image_size(&samples.dimensions),
+            samples,
+            layers,
+            selected_states,
+        }
+    }
+}
+
+/// Layer entities released by a reset, reused with their images instead of spawning new ones.
+#[derive(Resource, Default)]
+pub struct LayerPool {
+    free: Vec<(Entity, Handle<Image>)>,
+}
+
+impl LayerPool {
+    /// Number of hidden layers waiting for reuse.
+    pub fn len(&self) -> usize {
+        self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.free.is_empty()
+    }
 
+    /// Hides every layer of the stack and keeps it for reuse.
+    pub fn release_all(
+        &mut self,
+        commands: &mut Commands,
+        layers_q: &Query<(Entity, &Sprite), With<Layer>>,
+    ) {
+        for (entity, sprite) in layers_q.iter() {
+            commands
+                .entity(entity)
+                .remove::<Layer>()
+                .insert(Visibility::Hidden);
+            self.free.push((entity, sprite.image.clone()));
+        }
+    }
+
+    /// Shows a layer with `data` at `depth`, reusing a released entity and its image if any. The
+    /// buffer of a reused image goes to `buffers`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn spawn_layer(
+        &mut self,
+        commands: &mut Commands,
+        images: &mut Assets<Image>,
+        buffers: &RgbaBufferPool,
+        data: Vec<u8>,
+        width: u32,
+        height: u32,
+        depth: usize,
+        z: f32,
+    ) {
+        let layer = (
+            Layer { depth },
+            Transform::from_xyz(0.0, 0.0, z),
+            Visibility::Inherited,
+        );
+
+        match self.free.pop() {
+            Some((entity, handle)) => {
+                match images.get_mut(&handle) {
+                    Some(image) if image.size() == UVec2::new(width, height) => {
+                        if let Some(old) = image.data.replace(data) {
+                            buffers.release(old);
+                        }
+                    }
+                    _ => {
+                        images.insert(&handle, build_image(data, width, height));
+                    }
+                }
+                commands.entity(entity).insert(layer);
+            }
+            None => {
+                let handle = images.add(build_image(data, width, height));
+                commands.spawn((layer, Sprite::from_image(handle)));
+            }
+        }
+    }
+}
+
+/// RGBA8 buffers of replaced layer images, handed out again to render new layers instead of
+/// allocating. Clones share the same buffers, so background jobs can take from it too.
+#[derive(Resource, Clone, Default)]
+pub struct RgbaBufferPool {
+    free: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+impl RgbaBufferPool {
+    /// Number of buffers waiting for reuse.
+    pub fn len(&self) -> usize {
+        self.free.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Zeroed buffer of `len` bytes, reusing a released one with enough capacity if any.
+    pub fn take(&self, len: usize) -> Vec<u8> {
+        let reused = {
+            let mut free = self.free.lock().unwrap();
+            free.iter()
+                .position(|buffer| buffer.capacity() >= len)
+                .map(|index| free.swap_remove(index))
+        };
+        let mut buffer = reused.unwrap_or_default();
+        buffer.clear();
+        buffer.resize(len, 0);
+        buffer
+    }
+
+    pub fn release(&self, buffer: Vec<u8>) {
+        self.free.lock().unwrap().push(buffer);
+    }
+}
+
+/// Background task computing the next [`LayerBatch`].
+#[derive(Resource)]
+pub struct LayerTask<T> {
+    pub task: Option<Task<LayerBatch<T>>>,
+    /// Thread computing the remaining batches ahead with [`LayerData::prefetch_batches`].
+    pub producer: Option<Producer<LayerBatch<T>>>,
+}
+
+impl<T> Default for LayerTask<T> {
+    fn default() -> Self {
+        Self {
+            task: None,
+            producer: None,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn reset_layers_sys<T: ChaoticSystem + Clone>(
     mut commands: Commands,
     mut state: ResMut<ViewerState<T>>,
     init_data: Res<InitData<T>>,
     mut layer_data: ResMut<LayerData>,
-    layers_q: Query<Entity, With<Layer>>,
+    mut layer_task: ResMut<LayerTask<T>>,
+    mut history: ResMut<SampleHistory>,
+    mut pool: ResMut<LayerPool>,
+    layers_q: Query<(Entity, &Sprite), With<Layer>>,
     mut camera_q: Query<&mut Transform, With<MainCamera>>,
 ) -> Result<(), BevyError> {
     if layer_data.request_update {
-        for layer in layers_q.iter() {
-            commands.entity(layer).despawn();
-        }
+        pool.release_all(&mut commands, &layers_q);
 
+        // Dropping the task cancels it, its result would belong to the old run
+        layer_task.task = None;
+        layer_task.producer = None;
         *state = init_data.init();
+        history.clear();
 
         let mut camera_transform = camera_q.single_mut()?;
         camera_transform.translation.z -= layer_data.current_size();
 
         layer_data.current_depth = 0;
         layer_data.request_update = false;
+        layer_data.request_camera_fit = true;
     }
 
     Ok(())
 }
 
-pub fn process_layers_sys<T: ChaoticSystem>(
+/// Sent once the stack reaches [`LayerData::target_depth`], again only after a reset (or after the
+/// target is raised and reached again).
+#[derive(Event, Debug, Clone, Copy)]
+pub struct LayerStackComplete {
+    pub depth: usize,
+}
+
+/// Spawns layers computed by the background task and starts computing the next batch, or computes
+/// them in place with [`LayerData::deterministic`]. Layers released to the [`LayerPool`] are reused
+/// first.
+///
+/// The task works on a clone of the samples, so [`ViewerState::samples`] stays readable while a
+/// batch is in flight.
+#[allow(clippy::too_many_arguments)]
+pub fn process_layers_sys<T: ChaoticSystem + Clone>(
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
     mut state: ResMut<ViewerState<T>>,
     mut layer_data: ResMut<LayerData>,
+    mut layer_task: ResMut<LayerTask<T>>,
+    selected: Res<SelectedSample>,
+    mut history: ResMut<SampleHistory>,
+    mut pool: ResMut<LayerPool>,
+    buffers: Res<RgbaBufferPool>,
     mut camera_q: Query<&mut Transform, With<MainCamera>>,
+    mut complete: EventWriter<LayerStackComplete>,
 ) -> Result<(), BevyError> {
-    if layer_data.current_depth < layer_data.target_depth {
-        let dt = state.dt;
-        let updates_per_iteration = state.updates_per_iteration;
-        let start_time = Instant::now();
-        let mut current_time = start_time;
-
-        while current_time - start_time < Duration::from_millis(10) {
-            let mut camera_transform = camera_q.single_mut()?;
-            camera_transform.translation.z += layer_data.layers_gap;
-            state.samples.update(updates_per_iteration, dt);
-            let new_layer = build_image(&state.samples, &mut images);
-
-            commands.spawn((
-                Layer,
-                Sprite::from_image(new_layer.clone()),
-                Transform::from_xyz(0.0, 0.0, layer_data.current_size()),
-            ));
+    let mut batch = None;
+    if let Some(task) = &mut layer_task.task {
+        let Some(finished) = block_on(future::poll_once(task)) else {
+            return Ok(());
+        };
+        layer_task.task = None;
+        batch = Some(finished);
+    } else if let Some(producer) = &layer_task.producer {
+        match producer.try_next() {
+            Ok(finished) => batch = Some(finished),
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => layer_task.producer = None,
+        }
+    } else if layer_data.deterministic {
+        layer_data.apply_memory_budget(&state.samples.dimensions);
+        let count = layer_data.next_batch_len();
+        if count > 0 {
+            batch = Some(batch_job(&state, &layer_data, &selected, &buffers, count)());
+        }
+    }
+
+    if let Some(batch) = batch {
+        state.samples = batch.samples;
+        for selected_state in batch.selected_states {
+            history.push(selected_state);
+        }
+
+        let (width, height) = batch.size;
+        let mut camera_transform = camera_q.single_mut()?;
+        let was_complete = layer_data.current_depth >= layer_data.target_depth;
+
+        for data in batch.layers {
+            camera_transform.translation.z += layer_data.depth_spacing;
+            pool.spawn_layer(
+                &mut commands,
+                &mut images,
+                &buffers,
+                data,
+                width,
+                height,
+                layer_data.current_depth,
+                layer_data.current_size(),
+            );
 
             layer_data.current_depth += 1;
-            if layer_data.current_depth >= layer_data.target_depth {
-                break;
-            }
+        }
 
-            current_time = Instant::now();
+        if !was_complete && layer_data.current_depth >= layer_data.target_depth {
+            complete.write(LayerStackComplete {
+                depth: layer_data.current_depth,
+            });
         }
     }
 
+    if layer_data.deterministic || layer_task.producer.is_some() {
+        return Ok(());
+    }
+
+    layer_data.apply_memory_budget(&state.samples.dimensions);
+    if layer_data.prefetch_batches > 0 {
+        let remaining = layer_data
+            .target_depth
+            .saturating_sub(layer_data.current_depth);
+        if remaining > 0 {
+            let produce = producer_job(&state, &layer_data, &selected, &buffers, remaining);
+            layer_task.producer = Some(Producer::spawn(layer_data.prefetch_batches, produce));
+        }
+        return Ok(());
+    }
+
+    let count = layer_data.next_batch_len();
+    if count > 0 {
+        let job = batch_job(&state, &layer_data, &selected, &buffers, count);
+        layer_task.task = Some(AsyncComputeTaskPool::get().spawn(async move { job() }));
+    }
+
     Ok(())
 }
 
-fn build_image<T: ChaoticSystem>(
-    samples: &Samples<T>,
-    images: &mut Assets<Image>,
-) -> Handle<Image> {
-    assert_eq!(
-        samples.dimensions.len(),
-        2,
-        "Expected 2D dimensions for draw_2d"
-    );
+/// Captures what [`LayerBatch::compute`] needs to compute the next `count` layers.
+fn batch_job<T: ChaoticSystem + Clone>(
+    state: &ViewerState<T>,
+    layer_data: &LayerData,
+    selected: &SelectedSample,
+    buffers: &RgbaBufferPool,
+    count: usize,
+) -> impl FnOnce() -> LayerBatch<T> + Send + 'static {
+    let samples = state.samples.clone();
+    let dt = state.dt;
+    let updates_per_iteration = state.updates_per_iteration;
+    let selected_index = selected.index(&samples.dimensions);
+    let convergence_tol = layer_data.convergence_tol;
+    let render = layer_data.render;
+    let buffers = buffers.clone();
 
-    let width = samples.dimensions[0] as u32;
-    let height = samples.dimensions[1] as u32;
+    move || {
+        LayerBatch::compute(
+            samples,
+            count,
+            updates_per_iteration,
+            dt,
+            convergence_tol,
+            selected_index,
+            render,
+            &buffers,
+        )
+    }
+}
 
-    // Allocate RGBA8 buffer
-    let mut data = vec![0u8; (width * height * 4) as usize];
+/// Like [`batch_job`], but computes the `remaining` layers one batch per call, `None` once all of
+/// them are done.
+fn producer_job<T: ChaoticSystem + Clone>(
+    state: &ViewerState<T>,
+    layer_data: &LayerData,
+    selected: &SelectedSample,
+    buffers: &RgbaBufferPool,
+    mut remaining: usize,
+) -> impl FnMut() -> Option<LayerBatch<T>> + Send + 'static {
+    let mut samples = state.samples.clone();
+    let dt = state.dt;
+    let updates_per_iteration = state.updates_per_iteration;
+    let selected_index = selected.index(&samples.dimensions);
+    let convergence_tol = layer_data.convergence_tol;
+    let render = layer_data.render;
+    let buffers = buffers.clone();
+    let batch_len = layer_data.layers_per_batch.max(1);
 
-    for (index, pos) in samples.dimensions.iter().enumerate() {
-        let color = samples.samples[index].color();
+    move || {
+        if remaining == 0 {
+            return None;
+        }
+        let count = remaining.min(batch_len);
+        remaining -= count;
+
+        let batch = LayerBatch::compute(
+            samples.clone(),
+            count,
+            updates_per_iteration,
+            dt,
+            convergence_tol,
+            selected_index,
+            render,
+            &buffers,
+        );
+        samples = batch.samples.clone();
+        Some(batch)
+    }
+}
+
+/// Shows only the latest layer in [`CameraView::Flat`], every layer otherwise.
+pub fn layer_visibility_sys(
+    layer_data: Res<LayerData>,
+    mut layers_q: Query<(&Layer, &mut Visibility)>,
+) {
+    let latest = layer_data.current_depth.saturating_sub(1);
+    for (layer, mut visibility) in layers_q.iter_mut() {
+        let visible = !layer_data.camera_view.is_flat() || layer.depth >= latest;
+        visibility.set_if_neq(if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+/// Moves already spawned layers and the camera when `depth_spacing` is changed, so the whole stack
+/// keeps a uniform spacing.
+pub fn depth_spacing_sys(
+    layer_data: Res<LayerData>,
+    mut applied_spacing: Local<Option<f32>>,
+    mut layers_q: Query<(&Layer, &mut Transform), Without<MainCamera>>,
+    mut camera_q: Query<&mut Transform, With<MainCamera>>,
+) -> Result<(), BevyError> {
+    let prev_spacing = applied_spacing.replace(layer_data.depth_spacing);
+    let Some(prev_spacing) = prev_spacing else {
+        return Ok(());
+    };
+    if prev_spacing == layer_data.depth_spacing {
+        return Ok(());
+    }
 
-        let rgba = color.to_srgba();
-        let idx = (pos[1] as u32 * width + pos[0] as u32) as usize * 4;
-        data[idx] = (rgba.red * 255.0).round().clamp(0.0, 255.0) as u8;
-        data[idx + 1] = (rgba.green * 255.0).round().clamp(0.0, 255.0) as u8;
-        data[idx + 2] = (rgba.blue * 255.0).round().clamp(0.0, 255.0) as u8;
-        data[idx + 3] = (rgba.alpha * 255.0).round().clamp(0.0, 255.0) as u8;
+    for (layer, mut transform) in layers_q.iter_mut() {
+        transform.translation.z = layer_data.layer_z(layer.depth);
     }
 
-    let image = Image::new(
+    let mut camera_transform = camera_q.single_mut()?;
+    camera_transform.translation.z +=
+        (layer_data.depth_spacing - prev_spacing) * layer_data.current_depth as f32;
+
+    Ok(())
+}
+
+/// Wraps an RGBA8 buffer of a layer into an image. Empty layers give a transparent 1x1 image, as
+/// textures can't be empty.
+pub fn build_image(data: Vec<u8>, width: u32, height: u32) -> Image {
+    if width == 0 || height == 0 {
+        return build_image(vec![0; 4], 1, 1);
+    }
+    Image::new(
         Extent3d {
             width,
             height,
@@ -245,13 +691,633 @@ fn build_image<T: ChaoticSystem>(
         data,
         TextureFormat::Rgba8UnormSrgb,
         RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// How grid axes map onto image axes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageLayout {
+    /// First dimension along X, second along Y.
+    #[default]
+    RowMajor,
+    /// First dimension along Y, second along X.
+    Transposed,
+}
+
+impl ImageLayout {
+    /// Image `(width, height)` for 2D `dimensions`.
+    pub fn image_size(self, dimensions: &Dimensions) -> (u32, u32) {
+        let (x, y) = (dimensions[0] as u32, dimensions[1] as u32);
+        match self {
+            ImageLayout::RowMajor => (x, y),
+            ImageLayout::Transposed => (y, x),
+        }
+    }
+
+    /// Image `(x, y)` of the sample at grid `pos`.
+    pub fn pixel(self, pos: &[usize]) -> (u32, u32) {
+        let (x, y) = (pos[0] as u32, pos[1] as u32);
+        match self {
+            ImageLayout::RowMajor => (x, y),
+            ImageLayout::Transposed => (y, x),
+        }
+    }
+}
+
+/// Options for turning samples into pixels.
+#[derive(Debug, Clone, Copy)]
+pub struct RenderSettings {
+    pub layout: ImageLayout,
+    /// Ignore the alpha of sample colors. Otherwise layers are blended, so deeper layers show
+    /// through the translucent samples of the upper ones.
+    pub opaque: bool,
+    /// Exponent applied to every color channel, values below `1` lift faint structure.
+    pub gamma: f32,
+    /// Multiplier of every color channel, applied after `gamma`.
+    pub brightness: f32,
+    /// Multiply [`ChaoticSystem::alpha`] into the pixel alpha, fading out stable samples.
+    pub system_alpha: bool,
+    /// Average colors over this many sub-steps of a layer, see [`Samples::color_averaged`]. `1`
+    /// renders only the current state.
+    pub temporal_frames: usize,
+    /// Fade in perturbed or resumed samples over this many updates, see
+    /// [`Samples::fade_in_alpha`]. `0` disables the fade.
+    pub fade_in_frames: u32,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            layout: ImageLayout::default(),
+            opaque: false,
+            gamma: 1.0,
+            brightness: 1.0,
+            system_alpha: false,
+            temporal_frames: 1,
+            fade_in_frames: 0,
+        }
+    }
+}
+
+impl RenderSettings {
+    /// Applies `gamma` and `brightness` to a color channel in `[0, 1]`, alpha is left as is.
+    pub fn adjust_channel(&self, value: f32) -> f32 {
+        value.max(0.0).powf(self.gamma) * self.brightness
+    }
+}
+
+/// Renders 2D samples into an sRGB RGBA8 buffer, row by row.
+pub fn build_rgba<T: ChaoticSystem>(samples: &Samples<T>, render: &RenderSettings) -> Vec<u8> {
+    build_rgba_into(Vec::new(), samples, render)
+}
+
+/// Same as [`build_rgba`], reusing the allocation of `buffer`.
+pub fn build_rgba_into<T: ChaoticSystem>(
+    buffer: Vec<u8>,
+    samples: &Samples<T>,
+    render: &RenderSettings,
+) -> Vec<u8> {
+    build_rgba_with(buffer, samples, render, |index| samples.sample_color(index))
+}
+
+/// Same as [`build_rgba`], with colors averaged over [`RenderSettings::temporal_frames`] sub-steps
+/// spread over `layer_dt`, the time one layer advances the samples by. Reuses the allocation of
+/// `buffer`.
+pub fn build_rgba_averaged<T: ChaoticSystem + Clone>(
+    buffer: Vec<u8>,
+    samples: &Samples<T>,
+    render: &RenderSettings,
+    layer_dt: f64,
+) -> Vec<u8> {
+    let frames = render.temporal_frames.max(1);
+    build_rgba_with(buffer, samples, render, |index| {
+        samples.color_averaged(index, frames, layer_dt / frames as f64)
+    })
+}
+
+fn build_rgba_with<T: ChaoticSystem>(
+    mut data: Vec<u8>,
+    samples: &Samples<T>,
+    render: &RenderSettings,
+    sample_color: impl Fn(usize) -> Color,
+) -> Vec<u8> {
+    assert_eq!(
+        samples.dimensions.len(),
+        2,
+        "Expected 2D dimensions for draw_2d"
     );
 
-    images.add(image)
+    let (width, _) = render.layout.image_size(&samples.dimensions);
+
+    data.clear();
+    data.resize(samples.dimensions.volume() * BYTES_PER_PIXEL, 0);
+
+    for (index, pos) in samples.dimensions.iter().enumerate() {
+        let rgba = sample_color(index).to_srgba();
+        let (x, y) = render.layout.pixel(&pos);
+        let idx = (y * width + x) as usize * 4;
+        for (offset, channel) in [rgba.red, rgba.green, rgba.blue].into_iter().enumerate() {
+            let value = render.adjust_channel(channel);
+            data[idx + offset] = (value * 255.0).round().clamp(0.0, 255.0) as u8;
+        }
+        let alpha = if render.system_alpha {
+            rgba.alpha * samples.samples[index].alpha()
+        } else {
+            rgba.alpha
+        } * samples.fade_in_alpha(index, render.fade_in_frames);
+        data[idx + 3] = if render.opaque {
+            u8::MAX
+        } else {
+            (alpha * 255.0).round().clamp(0.0, 255.0) as u8
+        };
+    }
+
+    data
 }
 
-// Simple 2D rotation for DVec2 by angle (radians)
-fn rotate(v: DVec2, angle: f64) -> DVec2 {
-    let (s, c) = angle.sin_cos();
-    DVec2::new(v.x * c - v.y * s, v.x * s + v.y * c)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer_z(app: &mut App, depth: usize) -> f32 {
+        let mut query = app.world_mut().query::<(&Layer, &Transform)>();
+        query
+            .iter(app.world())
+            .find(|(layer, _)| layer.depth == depth)
+            .map(|(_, transform)| transform.translation.z)
+            .expect("layer not found")
+    }
+
+    #[test]
+    fn test_build_empty_image() {
+        let image = build_image(Vec::new(), 0, 3);
+        assert_eq!(image.size(), UVec2::ONE);
+    }
+
+    #[test]
+    fn test_depth_spacing() {
+        let mut app = App::new();
+        app.init_resource::<LayerData>()
+            .add_systems(Update, depth_spacing_sys);
+
+        app.world_mut()
+            .spawn((MainCamera::default(), Transform::default()));
+        for depth in 0..2 {
+            let z = app.world().resource::<LayerData>().layer_z(depth);
+            app.world_mut()
+                .spawn((Layer { depth }, Transform::from_xyz(0.0, 0.0, z)));
+        }
+        app.world_mut().resource_mut::<LayerData>().current_depth = 2;
+        app.update();
+
+        let initial_z = layer_z(&mut app, 1);
+        assert_eq!(layer_z(&mut app, 0), 0.0);
+
+        app.world_mut().resource_mut::<LayerData>().depth_spacing *= 2.0;
+        app.update();
+
+        assert_eq!(layer_z(&mut app, 0), 0.0);
+        assert_eq!(layer_z(&mut app, 1), initial_z * 2.0);
+
+        let mut camera_q = app
+            .world_mut()
+            .query_filtered::<&Transform, With<MainCamera>>();
+        let camera_z = camera_q.single(app.world()).unwrap().translation.z;
+        assert_eq!(
+            camera_z,
+            app.world().resource::<LayerData>().current_size() / 2.0
+        );
+    }
+
+    #[test]
+    fn test_flat_view_hides_lower_layers() {
+        let mut app = App::new();
+        app.insert_resource(LayerData {
+            current_depth: 3,
+            ..default()
+        })
+        .add_systems(Update, layer_visibility_sys);
+        for depth in 0..3 {
+            app.world_mut()
+                .spawn((Layer { depth }, Visibility::Inherited));
+        }
+
+        let visible = |app: &mut App, view: CameraView| {
+            app.world_mut().resource_mut::<LayerData>().camera_view = view;
+            app.update();
+            let mut layers_q = app.world_mut().query::<(&Layer, &Visibility)>();
+            let mut depths = layers_q
+                .iter(app.world())
+                .filter(|(_, visibility)| **visibility != Visibility::Hidden)
+                .map(|(layer, _)| layer.depth)
+                .collect::<Vec<_>>();
+            depths.sort();
+            depths
+        };
+
+        assert_eq!(visible(&mut app, CameraView::Flat), vec![2]);
+        assert_eq!(visible(&mut app, CameraView::Diagonal), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_memory_budget() {
+        let dimensions = Dimensions::new(vec![256, 256]);
+        // 256 KiB per layer
+        assert_eq!(max_depth_for_budget(&dimensions, 64 << 20), 256);
+        assert_eq!(max_depth_for_budget(&dimensions, (1 << 20) + 1), 4);
+        assert_eq!(max_depth_for_budget(&dimensions, 0), 1);
+
+        let mut layer_data = LayerData {
+            target_depth: 1000,
+            memory_budget: 16 << 20,
+            ..default()
+        };
+        layer_data.apply_memory_budget(&dimensions);
+        assert_eq!(layer_data.target_depth, 64);
+
+        layer_data.target_depth = 10;
+        layer_data.apply_memory_budget(&dimensions);
+        assert_eq!(layer_data.target_depth, 10);
+    }
+
+    fn small_init_data() -> InitData<Mandelbrot> {
+        InitData {
+            dimensions: Dimensions::new(vec![4, 3]),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_compute_layer_batch() {
+        let init_data = small_init_data();
+        let samples = init_data.init().samples;
+
+        let buffers = RgbaBufferPool::default();
+        let batch = LayerBatch::compute(
+            samples,
+            5,
+            1,
+            0.1,
+            None,
+            0,
+            RenderSettings::default(),
+            &buffers,
+        );
+        assert_eq!(batch.layers.len(), 5);
+        assert_eq!(batch.selected_states.len(), 5);
+        assert_eq!(batch.size, (4, 3));
+        for layer in &batch.layers {
+            assert_eq!(layer.len(), 4 * 3 * 4);
+        }
+
+        let transposed = RenderSettings {
+            layout: ImageLayout::Transposed,
+            ..default()
+        };
+        let batch = LayerBatch::compute(batch.samples, 1, 1, 0.1, None, 0, transposed, &buffers);
+        assert_eq!(batch.size, (3, 4));
+    }
+
+    fn layers_app() -> App {
+        let init_data = small_init_data();
+
+        let mut app = App::new();
+        app.add_plugins(TaskPoolPlugin::default())
+            .insert_resource(init_data.init())
+            .insert_resource(init_data)
+            .insert_resource(LayerData {
+                target_depth: 10,
+                layers_per_batch: 4,
+                ..default()
+            })
+            .init_resource::<LayerTask<Mandelbrot>>()
+            .init_resource::<LayerPool>()
+            .init_resource::<RgbaBufferPool>()
+            .init_resource::<SelectedSample>()
+            .init_resource::<SampleHistory>()
+            .init_resource::<Assets<Image>>()
+            .init_resource::<Completions>()
+            .add_event::<LayerStackComplete>()
+            .add_systems(
+                Update,
+                (
+                    reset_layers_sys::<Mandelbrot>,
+                    process_layers_sys::<Mandelbrot>,
+                    count_completions_sys,
+                )
+                    .chain(),
+            );
+        app.world_mut()
+            .spawn((MainCamera::default(), Transform::default()));
+
+        app
+    }
+
+    #[derive(Resource, Default)]
+    struct Completions(Vec<usize>);
+
+    fn count_completions_sys(
+        mut events: EventReader<LayerStackComplete>,
+        mut completions: ResMut<Completions>,
+    ) {
+        completions
+            .0
+            .extend(events.read().map(|complete| complete.depth));
+    }
+
+    fn update_until_depth(app: &mut App, depth: usize) {
+        for _ in 0..1000 {
+            app.update();
+            if app.world().resource::<LayerData>().current_depth >= depth {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_deterministic_layers_per_tick() {
+        let mut app = layers_app();
+        {
+            let mut layer_data = app.world_mut().resource_mut::<LayerData>();
+            layer_data.deterministic = true;
+            layer_data.layers_per_frame = 3;
+        }
+
+        for expected in [3, 6, 9, 10, 10] {
+            app.update();
+            assert_eq!(app.world().resource::<LayerData>().current_depth, expected);
+            assert!(app
+                .world()
+                .resource::<LayerTask<Mandelbrot>>()
+                .task
+                .is_none());
+        }
+        assert_eq!(app.world().resource::<SampleHistory>().len(), 10);
+    }
+
+    #[test]
+    fn test_prefetched_batches() {
+        let mut app = layers_app();
+        app.world_mut().resource_mut::<LayerData>().prefetch_batches = 2;
+        update_until_depth(&mut app, 10);
+
+        assert_eq!(app.world().resource::<LayerData>().current_depth, 10);
+        assert_eq!(app.world().resource::<SampleHistory>().len(), 10);
+        let mut layers_q = app.world_mut().query::<&Layer>();
+        let mut depths = layers_q
+            .iter(app.world())
+            .map(|layer| layer.depth)
+            .collect::<Vec<_>>();
+        depths.sort();
+        assert_eq!(depths, (0..10).collect::<Vec<_>>());
+
+        // The finished producer is dropped and nothing more is computed
+        app.update();
+        app.update();
+        assert!(app
+            .world()
+            .resource::<LayerTask<Mandelbrot>>()
+            .producer
+            .is_none());
+        assert_eq!(app.world().resource::<LayerData>().current_depth, 10);
+    }
+
+    #[test]
+    fn test_process_layers_spawns_batches() {
+        let mut app = layers_app();
+        update_until_depth(&mut app, 10);
+
+        assert_eq!(app.world().resource::<LayerData>().current_depth, 10);
+        assert_eq!(app.world().resource::<SampleHistory>().len(), 10);
+        assert!(app
+            .world()
+            .resource::<LayerTask<Mandelbrot>>()
+            .task
+            .is_none());
+
+        let mut layers_q = app.world_mut().query::<(&Layer, &Sprite)>();
+        let mut depths = layers_q
+            .iter(app.world())
+            .map(|(layer, sprite)| {
+                let images = app.world().resource::<Assets<Image>>();
+                assert!(images.get(&sprite.image).is_some());
+                layer.depth
+            })
+            .collect::<Vec<_>>();
+        depths.sort();
+        assert_eq!(depths, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_stack_complete_once() {
+        let mut app = layers_app();
+        update_until_depth(&mut app, 10);
+        for _ in 0..5 {
+            app.update();
+        }
+        assert_eq!(app.world().resource::<Completions>().0, vec![10]);
+
+        app.world_mut().resource_mut::<LayerData>().request_update = true;
+        app.update();
+        update_until_depth(&mut app, 10);
+        for _ in 0..5 {
+            app.update();
+        }
+        assert_eq!(app.world().resource::<Completions>().0, vec![10, 10]);
+    }
+
+    #[test]
+    fn test_reset_reuses_layers() {
+        let mut app = layers_app();
+        update_until_depth(&mut app, 10);
+
+        let mut sprites_q = app.world_mut().query::<(Entity, &Sprite)>();
+        let mut entities = sprites_q
+            .iter(app.world())
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        entities.sort();
+        assert_eq!(entities.len(), 10);
+        let image_count = app.world().resource::<Assets<Image>>().len();
+
+        app.world_mut().resource_mut::<LayerData>().request_update = true;
+        app.update();
+        assert_eq!(app.world().resource::<LayerPool>().len(), 10);
+        let mut layers_q = app.world_mut().query::<&Layer>();
+        assert!(layers_q.iter(app.world()).len() < 10);
+
+        update_until_depth(&mut app, 10);
+        assert_eq!(app.world().resource::<LayerData>().current_depth, 10);
+        assert!(app.world().resource::<LayerPool>().is_empty());
+
+        let mut reused = sprites_q
+            .iter(app.world())
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        reused.sort();
+        assert_eq!(reused, entities);
+        assert_eq!(layers_q.iter(app.world()).len(), 10);
+        assert_eq!(app.world().resource::<Assets<Image>>().len(), image_count);
+    }
+
+    #[test]
+    fn test_buffer_pool_reuse() {
+        let samples = small_init_data().init().samples;
+        let buffers = RgbaBufferPool::default();
+
+        let first = build_rgba_into(buffers.take(48), &samples, &RenderSettings::default());
+        let (ptr, capacity) = (first.as_ptr(), first.capacity());
+        buffers.release(first);
+        assert_eq!(buffers.len(), 1);
+
+        let second = build_rgba_into(buffers.take(48), &samples, &RenderSettings::default());
+        assert_eq!((second.as_ptr(), second.capacity()), (ptr, capacity));
+        assert_eq!(second, build_rgba(&samples, &RenderSettings::default()));
+        assert!(buffers.is_empty());
+
+        // Too small buffers aren't handed out
+        buffers.release(Vec::with_capacity(8));
+        assert!(buffers.take(48).capacity() >= 48);
+        assert_eq!(buffers.len(), 1);
+    }
+
+    #[test]
+    fn test_transposed_layout() {
+        let init_data = small_init_data();
+        let mut samples = init_data.init().samples;
+        samples.update(3, 0.1);
+
+        let row_major = build_rgba(&samples, &RenderSettings::default());
+        let transposed = build_rgba(
+            &samples,
+            &RenderSettings {
+                layout: ImageLayout::Transposed,
+                ..default()
+            },
+        );
+
+        let (width, height) = ImageLayout::RowMajor.image_size(&samples.dimensions);
+        assert_eq!((width, height), (4, 3));
+        assert_eq!(
+            ImageLayout::Transposed.image_size(&samples.dimensions),
+            (height, width)
+        );
+
+        for y in 0..height {
+            for x in 0..width {
+                let default_idx = ((y * width + x) * 4) as usize;
+                let transposed_idx = ((x * height + y) * 4) as usize;
+                assert_eq!(
+                    row_major[default_idx..default_idx + 4],
+                    transposed[transposed_idx..transposed_idx + 4]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_alpha_from_color() {
+        let init_data = small_init_data();
+        let mut samples = init_data.init().samples;
+        samples.update(3, 0.1);
+
+        let blended = build_rgba(&samples, &RenderSettings::default());
+        let opaque = build_rgba(
+            &samples,
+            &RenderSettings {
+                opaque: true,
+                ..default()
+            },
+        );
+
+        let mut translucent = 0;
+        for (index, _) in samples.dimensions.iter().enumerate() {
+            let alpha = samples.sample_color(index).to_srgba().alpha;
+            let expected = (alpha * 255.0).round() as u8;
+            assert_eq!(blended[index * 4 + 3], expected);
+            assert_eq!(opaque[index * 4 + 3], u8::MAX);
+            assert_eq!(
+                blended[index * 4..index * 4 + 3],
+                opaque[index * 4..index * 4 + 3]
+            );
+            if expected < u8::MAX {
+                translucent += 1;
+            }
+        }
+        assert!(translucent > 0);
+    }
+
+    #[test]
+    fn test_system_alpha() {
+        let init_data = small_init_data();
+        let mut samples = init_data.init().samples;
+        samples.update(3, 0.1);
+
+        let plain = build_rgba(&samples, &RenderSettings::default());
+        let faded = build_rgba(
+            &samples,
+            &RenderSettings {
+                system_alpha: true,
+                ..default()
+            },
+        );
+
+        for (index, system) in samples.samples.iter().enumerate() {
+            let color_alpha = samples.sample_color(index).to_srgba().alpha;
+            let expected = (color_alpha * system.alpha() * 255.0).round() as u8;
+            assert_eq!(faded[index * 4 + 3], expected);
+            assert_eq!(
+                faded[index * 4..index * 4 + 3],
+                plain[index * 4..index * 4 + 3]
+            );
+        }
+    }
+
+    #[test]
+    fn test_gamma_brightness() {
+        let init_data = small_init_data();
+        let mut samples = init_data.init().samples;
+        samples.update(3, 0.1);
+
+        let identity = RenderSettings {
+            gamma: 1.0,
+            brightness: 1.0,
+            ..default()
+        };
+        for value in [0.0, 0.25, 0.5, 1.0] {
+            assert_eq!(identity.adjust_channel(value), value);
+        }
+        let plain = build_rgba(&samples, &RenderSettings::default());
+        assert_eq!(build_rgba(&samples, &identity), plain);
+
+        let gamma = RenderSettings {
+            gamma: 2.0,
+            ..default()
+        };
+        assert_eq!(gamma.adjust_channel(0.5), 0.25);
+        assert_eq!(gamma.adjust_channel(1.0), 1.0);
+
+        let darkened = build_rgba(&samples, &gamma);
+        for (index, (&adjusted, &value)) in darkened.iter().zip(&plain).enumerate() {
+            if index % 4 == 3 {
+                assert_eq!(adjusted, value);
+                continue;
+            }
+            let expected = (value as f32 / 255.0).powi(2) * 255.0;
+            assert!(
+                (adjusted as f32 - expected).abs() <= 1.0,
+                "{} vs {}",
+                adjusted,
+                expected
+            );
+        }
+
+        let bright = RenderSettings {
+            brightness: 2.0,
+            ..default()
+        };
+        assert_eq!(bright.adjust_channel(0.25), 0.5);
+        assert_eq!(bright.adjust_channel(0.75), 1.5);
+    }
 }
@@ -1,9 +1,27 @@
 mod camera;
+mod cli;
+mod export;
+mod focus;
 mod gui;
+mod inspect;
 mod layers;
+mod orbits;
+mod prefetch;
+mod randomize;
+mod snapshot;
+mod speed;
 mod visualize_area;
 
 pub use camera::*;
+pub use cli::*;
+pub use export::*;
+pub use focus::*;
 pub use gui::*;
+pub use inspect::*;
 pub use layers::*;
+pub use orbits::*;
+pub use prefetch::*;
+pub use randomize::*;
+pub use snapshot::*;
+pub use speed::*;
 pub use visualize_area::*;
@@ -6,14 +6,59 @@ use viewer::*;
 type System = chaotic::NBody;
 
 fn main() {
+    let args = match CliArgs::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, CLI_USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut init_data = InitData::<System>::default();
+    args.apply_to_init_data(&mut init_data);
+    if let Some(path) = &args.system {
+        match read_system(path) {
+            Ok(system) => init_data.initial_sample = system,
+            Err(err) => eprintln!("{}, using the default system", err),
+        }
+    }
+
+    let mut window = Window {
+        title: System::name().to_string(),
+        ..default()
+    };
+    args.apply_to_window(&mut window);
+
+    let mut snapshot_file = SnapshotFile::default();
+    if let Some(path) = &args.load {
+        snapshot_file.path = path.clone();
+        snapshot_file.request_load = true;
+    }
+
     App::new()
         .init_gizmo_group::<AreaGizmos>()
-        .add_plugins(DefaultPlugins)
+        .init_gizmo_group::<OrbitGizmos>()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(window),
+            ..default()
+        }))
         .add_plugins(EguiPlugin::default())
         .init_resource::<ClearColor>()
         .insert_resource(ClearColor(Color::BLACK))
-        .init_resource::<InitData<System>>()
+        .insert_resource(init_data)
         .init_resource::<LayerData>()
+        .init_resource::<LayerTask<System>>()
+        .init_resource::<LayerPool>()
+        .init_resource::<RgbaBufferPool>()
+        .init_resource::<SelectedSample>()
+        .init_resource::<SampleHistory>()
+        .init_resource::<GifExport>()
+        .init_resource::<LayerRecorder>()
+        .init_resource::<OrbitPreview>()
+        .init_resource::<FocusView>()
+        .init_resource::<RandomRestart>()
+        .insert_resource(snapshot_file)
+        .add_event::<LayerStackComplete>()
         .add_systems(Startup, setup::<System>)
         .add_systems(
             Update,
@@ -21,12 +66,31 @@ fn main() {
                 camera_zoom,
                 camera_move_by_mouse,
                 rotate_camera,
+                adjust_speed_sys::<System>,
+                select_sample_by_keys_sys::<System>,
+                depth_spacing_sys,
+                layer_visibility_sys,
+                fit_camera_to_grid_sys::<System>,
                 reset_layers_sys::<System>,
                 process_layers_sys::<System>,
                 visualize_area::<System>,
+                orbit_preview_sys,
+                export_gif_sys,
+                recorder_hotkey_sys,
+                record_layers_sys,
+                snapshot_hotkey_sys,
+                save_snapshot_sys::<System>,
+                load_snapshot_sys::<System>,
+            ),
+        )
+        .add_systems(
+            EguiPrimaryContextPass,
+            (
+                focus_view_sys,
+                gui_system::<System>,
+                stats_window_sys::<System>,
             ),
         )
-        .add_systems(EguiPrimaryContextPass, gui_system::<System>)
         .run();
 }
 
@@ -34,7 +98,7 @@ fn setup<T: ChaoticSystem + Clone>(mut commands: Commands, init_data: Res<InitDa
     // 2D camera is enough for now; we stack layers along Z
     commands.spawn((
         Camera2d,
-        Transform::from_translation(Vec3::ONE * 10000.0).looking_at(Vec3::ZERO, Vec3::Z),
+        grid_view(0.0),
         MainCamera::default(),
         Projection::Orthographic(OrthographicProjection {
             far: 200000.0,
@@ -0,0 +1,123 @@
+use crate::{LayerData, SelectedSample, ViewerState};
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use chaotic::{ChaoticSystem, NBody};
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct OrbitGizmos;
+
+/// Predicted paths of the bodies of the selected [`NBody`] sample, drawn over the top layer.
+#[derive(Resource)]
+pub struct OrbitPreview {
+    pub enabled: bool,
+    /// Number of updates integrated ahead.
+    pub horizon: usize,
+    /// Pixels per unit of the system space.
+    pub scale: f32,
+}
+
+impl Default for OrbitPreview {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            horizon: 256,
+            scale: 32.0,
+        }
+    }
+}
+
+/// Integrates a clone of `system` `horizon` updates of `dt` ahead and returns the path of every
+/// body, starting from its current position.
+pub fn predict_orbits(system: &NBody, horizon: usize, dt: f64) -> Vec<Vec<DVec2>> {
+    let mut system = system.clone();
+    let mut orbits = system
+        .iter()
+        .map(|body| {
+            let mut orbit = Vec::with_capacity(horizon + 1);
+            orbit.push(body.position);
+            orbit
+        })
+        .collect::<Vec<_>>();
+
+    for _ in 0..horizon {
+        system.update(dt);
+        for (orbit, body) in orbits.iter_mut().zip(system.iter()) {
+            orbit.push(body.position);
+        }
+    }
+
+    orbits
+}
+
+/// Draws [`predict_orbits`] of the selected sample around its cell of the top layer, every body
+/// in the color of its current velocity.
+pub fn orbit_preview_sys(
+    preview: Res<OrbitPreview>,
+    state: Res<ViewerState<NBody>>,
+    selected: Res<SelectedSample>,
+    layer_data: Res<LayerData>,
+    mut gizmos: Gizmos<OrbitGizmos>,
+) {
+    if !preview.enabled {
+        return;
+    }
+
+    let dimensions = &state.samples.dimensions;
+    let index = selected.index(dimensions);
+    let system = &state.samples.samples[index];
+
+    // Sprites are centered on the grid, with image rows going down
+    let layout = layer_data.render.layout;
+    let (width, height) = layout.image_size(dimensions);
+    let (x, y) = layout.pixel(&dimensions.index_to_pos(index));
+    let center = vec3(
+        x as f32 + 0.5 - width as f32 / 2.0,
+        height as f32 / 2.0 - y as f32 - 0.5,
+        layer_data.current_size(),
+    );
+
+    let orbits = predict_orbits(system, preview.horizon, state.dt);
+    for (orbit, color) in orbits.into_iter().zip(system.body_colors()) {
+        let points = orbit
+            .into_iter()
+            .filter(|position| position.is_finite())
+            .map(|position| center + position.as_vec2().extend(0.0) * preview.scale);
+        gizmos.linestrip(points, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chaotic::{Body, NBodyColorSchema};
+
+    #[test]
+    fn test_predict_orbits() {
+        let system = NBody::new(
+            1.0,
+            vec![
+                Body::new(0.1, DVec2::X, DVec2::Y * 0.2),
+                Body::new(0.1, DVec2::NEG_X, DVec2::NEG_Y * 0.2),
+            ],
+            NBodyColorSchema::VelocityToRgb { v0: 1.0 },
+        );
+
+        let orbits = predict_orbits(&system, 50, 0.1);
+        assert_eq!(orbits.len(), 2);
+        for (orbit, body) in orbits.iter().zip(system.iter()) {
+            assert_eq!(orbit.len(), 51);
+            assert_eq!(orbit[0], body.position);
+            assert_ne!(orbit[50], body.position);
+        }
+
+        let mut stepped = system.clone();
+        for _ in 0..50 {
+            stepped.update(0.1);
+        }
+        assert_eq!(orbits[1][50], stepped.bodies[1].position);
+
+        assert!(predict_orbits(&system, 0, 0.1)
+            .iter()
+            .all(|orbit| orbit.len() == 1));
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::mpsc::{sync_channel, Receiver, TryRecvError};
+use std::sync::Mutex;
+
+/// Runs `produce` on a thread of its own until it returns `None`, handing the items over through a
+/// channel holding at most `bound` of them: the thread blocks once that many are waiting, so it
+/// can't run arbitrarily far ahead of the consumer. Dropping the producer stops the thread after
+/// its current item.
+pub struct Producer<M> {
+    /// Behind a mutex only to be `Sync`, it is never contended.
+    receiver: Mutex<Receiver<M>>,
+}
+
+impl<M: Send + 'static> Producer<M> {
+    pub fn spawn(bound: usize, mut produce: impl FnMut() -> Option<M> + Send + 'static) -> Self {
+        let (sender, receiver) = sync_channel(bound.max(1));
+        std::thread::spawn(move || {
+            while let Some(item) = produce() {
+                if sender.send(item).is_err() {
+                    break;
+                }
+            }
+        });
+        Producer {
+            receiver: Mutex::new(receiver),
+        }
+    }
+
+    /// Next item in production order, [`TryRecvError::Empty`] if it isn't ready yet and
+    /// [`TryRecvError::Disconnected`] once every item was taken.
+    pub fn try_next(&self) -> Result<M, TryRecvError> {
+        self.receiver.lock().unwrap().try_recv()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bounded_and_ordered() {
+        let produced = Arc::new(AtomicUsize::new(0));
+        let producer = {
+            let produced = produced.clone();
+            Producer::spawn(3, move || {
+                let item = produced.fetch_add(1, Ordering::SeqCst);
+                (item < 10).then_some(item)
+            })
+        };
+
+        // Three items wait in the channel and the fourth blocks in `send`
+        for _ in 0..1000 {
+            if produced.load(Ordering::SeqCst) >= 4 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(produced.load(Ordering::SeqCst), 4);
+
+        let mut items = Vec::new();
+        loop {
+            match producer.try_next() {
+                Ok(item) => items.push(item),
+                Err(TryRecvError::Empty) => std::thread::sleep(Duration::from_millis(1)),
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+    }
+}
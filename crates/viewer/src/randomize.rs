@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Settings of the "Randomize initial" action, re-rolling `InitData::initial_mutation` to jump
+/// to a new region once the current grid has settled.
+#[derive(Resource)]
+pub struct RandomRestart {
+    /// Bounds every mutation value is drawn from.
+    pub min: f64,
+    pub max: f64,
+    /// Seed of the next roll, advanced after every roll so the sequence is reproducible.
+    pub seed: u64,
+}
+
+impl Default for RandomRestart {
+    fn default() -> Self {
+        Self {
+            min: -1.0,
+            max: 1.0,
+            seed: 0,
+        }
+    }
+}
+
+impl RandomRestart {
+    /// Draws `len` mutation values with the current seed and advances it.
+    pub fn roll(&mut self, len: usize) -> Vec<f64> {
+        let values = random_mutation(len, self.min, self.max, self.seed);
+        self.seed = self.seed.wrapping_add(1);
+        values
+    }
+}
+
+/// `len` values uniformly distributed in `[min, max]`, the same for the same `seed`.
+pub fn random_mutation(len: usize, min: f64, max: f64, seed: u64) -> Vec<f64> {
+    let (min, max) = if min <= max { (min, max) } else { (max, min) };
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..len).map(|_| rng.gen_range(min..=max)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_mutation_bounds_and_seed() {
+        let values = random_mutation(1000, -0.5, 2.0, 42);
+        assert_eq!(values.len(), 1000);
+        assert!(values.iter().all(|value| (-0.5..=2.0).contains(value)));
+        assert!(values.iter().any(|&value| value < 0.0));
+
+        assert_eq!(random_mutation(1000, -0.5, 2.0, 42), values);
+        assert_ne!(random_mutation(1000, -0.5, 2.0, 43), values);
+
+        // Swapped bounds still work, a single point is allowed
+        assert!(random_mutation(10, 1.0, -1.0, 0)
+            .iter()
+            .all(|value| value.abs() <= 1.0));
+        assert_eq!(random_mutation(3, 0.25, 0.25, 7), vec![0.25; 3]);
+
+        let mut restart = RandomRestart::default();
+        let first = restart.roll(4);
+        assert_eq!(first, random_mutation(4, -1.0, 1.0, 0));
+        assert_ne!(restart.roll(4), first);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    InitData,
+    Layer,
+    LayerData,
+    LayerPool,
+    LayerTask,
+    MainCamera,
+    SampleHistory,
+    ViewerState,
+};
+use bevy::prelude::*;
+use chaotic::ChaoticSystem;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[derive(Resource)]
+pub struct SnapshotFile {
+    pub path: String,
+    pub request_save: bool,
+    pub request_load: bool,
+}
+
+impl Default for SnapshotFile {
+    fn default() -> Self {
+        Self {
+            path: "snapshot.ron".to_string(),
+            request_save: false,
+            request_load: false,
+        }
+    }
+}
+
+/// Serializes the run configuration together with the current samples as RON.
+pub fn encode_snapshot<T: Serialize>(
+    init_data: &InitData<T>,
+    state: &ViewerState<T>,
+) -> Result<Vec<u8>, ron::Error> {
+    Ok(ron::to_string(&(init_data, state))?.into_bytes())
+}
+
+/// Reads a snapshot written by [`encode_snapshot`].
+pub fn decode_snapshot<T: DeserializeOwned>(
+    bytes: &[u8],
+) -> Result<(InitData<T>, ViewerState<T>), ron::error::SpannedError> {
+    ron::de::from_bytes(bytes)
+}
+
+/// Requests a snapshot save on `F5`.
+pub fn snapshot_hotkey_sys(keys: Res<ButtonInput<KeyCode>>, mut file: ResMut<SnapshotFile>) {
+    if keys.just_pressed(KeyCode::F5) {
+        file.request_save = true;
+    }
+}
+
+pub fn save_snapshot_sys<T: ChaoticSystem + Serialize>(
+    mut file: ResMut<SnapshotFile>,
+    init_data: Res<InitData<T>>,
+    state: Res<ViewerState<T>>,
+) -> Result<(), BevyError> {
+    if !file.request_save {
+        return Ok(());
+    }
+    file.request_save = false;
+
+    let saved = encode_snapshot(&init_data, &state)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| std::fs::write(&file.path, bytes).map_err(|err| err.to_string()));
+    match saved {
+        Ok(()) => info!("Saved snapshot to {}", file.path),
+        Err(err) => error!("Failed to save snapshot to {}: {err}", file.path),
+    }
+
+    Ok(())
+}
+
+/// Replaces the run with the one stored in [`SnapshotFile::path`]. The loaded samples continue
+/// from where they were saved, but the layer stack starts over.
+#[allow(clippy::too_many_arguments)]
+pub fn load_snapshot_sys<T: ChaoticSystem + DeserializeOwned>(
+    mut commands: Commands,
+    mut file: ResMut<SnapshotFile>,
+    mut init_data: ResMut<InitData<T>>,
+    mut state: ResMut<ViewerState<T>>,
+    mut layer_data: ResMut<LayerData>,
+    mut layer_task: ResMut<LayerTask<T>>,
+    mut history: ResMut<SampleHistory>,
+    mut pool: ResMut<LayerPool>,
+    layers_q: Query<(Entity, &Sprite), With<Layer>>,
+    mut camera_q: Query<&mut Transform, With<MainCamera>>,
+) -> Result<(), BevyError> {
+    if !file.request_load {
+        return Ok(());
+    }
+    file.request_load = false;
+
+    let loaded = std::fs::read(&file.path)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| decode_snapshot(&bytes).map_err(|err| err.to_string()));
+    let (loaded_init_data, loaded_state) = match loaded {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            error!("Failed to load snapshot from {}: {err}", file.path);
+            return Ok(());
+        }
+    };
+    *init_data = loaded_init_data;
+    *state = loaded_state;
+    state.samples.enable_color_cache();
+
+    pool.release_all(&mut commands, &layers_q);
+    layer_task.task = None;
+    layer_task.producer = None;
+    history.clear();
+
+    let mut camera_transform = camera_q.single_mut()?;
+    camera_transform.translation.z -= layer_data.current_size();
+    layer_data.current_depth = 0;
+    layer_data.request_camera_fit = true;
+
+    info!("Loaded snapshot from {}", file.path);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chaotic::{Dimensions, Mandelbrot};
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let init_data = InitData::<Mandelbrot> {
+            dimensions: Dimensions::new(vec![3, 2]),
+            ..default()
+        };
+        let mut state = init_data.init();
+        state.samples.update(4, 0.1);
+        state.updates_per_iteration = 3;
+
+        let bytes = encode_snapshot(&init_data, &state).unwrap();
+        let (loaded_init_data, loaded_state) = decode_snapshot::<Mandelbrot>(&bytes).unwrap();
+
+        assert_eq!(
+            encode_snapshot(&loaded_init_data, &loaded_state).unwrap(),
+            bytes
+        );
+        assert_eq!(loaded_init_data.dimensions.sizes(), &[3, 2]);
+        assert_eq!(loaded_state.updates_per_iteration, 3);
+        for ((_, loaded), (_, sample)) in loaded_state.samples.iter().zip(state.samples.iter()) {
+            assert_eq!(loaded.state_vector(), sample.state_vector());
+        }
+    }
+
+    #[test]
+    fn test_snapshot_without_sample_caches() {
+        let init_data = InitData::<Mandelbrot> {
+            dimensions: Dimensions::new(vec![2, 2]),
+            ..default()
+        };
+        let state = init_data.init();
+
+        let text = String::from_utf8(encode_snapshot(&init_data, &state).unwrap()).unwrap();
+        assert!(!text.contains("color_cache:"));
+        let older = text.replace("active:[],", "");
+        assert!(!older.contains("active:"));
+
+        let (_, loaded_state) = decode_snapshot::<Mandelbrot>(older.as_bytes()).unwrap();
+        assert_eq!(loaded_state.samples.samples.len(), 4);
+        assert_eq!(loaded_state.samples.active_count(), 4);
+    }
+}
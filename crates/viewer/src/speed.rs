@@ -0,0 +1,92 @@
+use crate::ViewerState;
+use bevy::prelude::*;
+use chaotic::ChaoticSystem;
+
+const MIN_UPDATES_PER_ITERATION: usize = 1;
+const MAX_UPDATES_PER_ITERATION: usize = 1000;
+
+/// Doubles or halves `updates_per_iteration` on `+`/`-` key presses, so the run can be slowed down
+/// near interesting transitions without re-initializing.
+pub fn adjust_speed_sys<T: ChaoticSystem>(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<ViewerState<T>>,
+) {
+    let increase = keys.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]);
+    let decrease = keys.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]);
+
+    let mut updates = state.updates_per_iteration;
+    if increase {
+        updates = updates.saturating_mul(2);
+    }
+    if decrease {
+        updates /= 2;
+    }
+    let updates = updates.clamp(MIN_UPDATES_PER_ITERATION, MAX_UPDATES_PER_ITERATION);
+
+    if updates != state.updates_per_iteration {
+        state.updates_per_iteration = updates;
+        info!("Updates per iteration: {}", updates);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InitData;
+    use chaotic::{Dimensions, Mandelbrot};
+
+    fn test_app() -> App {
+        let init_data = InitData::<Mandelbrot> {
+            dimensions: Dimensions::new(vec![2, 2]),
+            ..default()
+        };
+
+        let mut app = App::new();
+        app.insert_resource(init_data.init())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .add_systems(Update, adjust_speed_sys::<Mandelbrot>);
+        app
+    }
+
+    fn press(app: &mut App, key: KeyCode) {
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.clear();
+        keys.release_all();
+        keys.press(key);
+        app.update();
+    }
+
+    fn updates_per_iteration(app: &App) -> usize {
+        app.world()
+            .resource::<ViewerState<Mandelbrot>>()
+            .updates_per_iteration
+    }
+
+    #[test]
+    fn test_adjust_speed() {
+        let mut app = test_app();
+        assert_eq!(updates_per_iteration(&app), 1);
+
+        press(&mut app, KeyCode::Equal);
+        assert_eq!(updates_per_iteration(&app), 2);
+
+        press(&mut app, KeyCode::NumpadAdd);
+        assert_eq!(updates_per_iteration(&app), 4);
+
+        press(&mut app, KeyCode::Minus);
+        assert_eq!(updates_per_iteration(&app), 2);
+
+        press(&mut app, KeyCode::Minus);
+        press(&mut app, KeyCode::Minus);
+        assert_eq!(updates_per_iteration(&app), 1);
+    }
+
+    #[test]
+    fn test_adjust_speed_upper_bound() {
+        let mut app = test_app();
+        for _ in 0..20 {
+            press(&mut app, KeyCode::Equal);
+        }
+        assert_eq!(updates_per_iteration(&app), MAX_UPDATES_PER_ITERATION);
+    }
+}
@@ -1,40 +1,112 @@
 use crate::*;
 use bevy::prelude::*;
-use chaotic::ChaoticSystem;
+use chaotic::{AxisDirection, ChaoticSystem};
 
 // We can create our own gizmo config group!
 #[derive(Default, Reflect, GizmoConfigGroup)]
 pub struct AreaGizmos;
 
+/// Region the next redraw (configured by `init_data`) covers in the current grid (`state`), in
+/// grid cells relative to the origin cell: the offset of its center and its half-size. Sprite Y
+/// points up while mutations grow with image rows, so Y is flipped.
+pub fn area_box<T>(state: &ViewerState<T>, init_data: &InitData<T>) -> (Vec2, Vec2) {
+    let (x_scale, y_scale) = cell_scales(state);
+    let new_scales = init_data.axis_scales();
+    let x_new_scale = (init_data.all_scale * new_scales[0]).abs();
+    let y_new_scale = (init_data.all_scale * new_scales[1]).abs();
+
+    let new_origin = origin_mutation(&init_data.initial_mutation, &init_data.axis_offsets);
+    let origin = origin_mutation(&state.initial_mutation, &state.axis_offsets);
+    let delta_x = new_origin[0] - origin[0];
+    let delta_y = origin[1] - new_origin[1];
+
+    let center = vec2((delta_x / x_scale) as f32, (delta_y / y_scale) as f32);
+    let half_size = vec2(
+        (init_data.dimensions[0] as f64 / 2.0 * x_new_scale / x_scale.abs()) as f32,
+        (init_data.dimensions[1] as f64 / 2.0 * y_new_scale / y_scale.abs()) as f32,
+    );
+
+    (center, half_size)
+}
+
+/// Mutation of the origin cell: `initial_mutation` with the axis offsets added.
+fn origin_mutation(initial_mutation: &[f64], axis_offsets: &[f64]) -> Vec<f64> {
+    let mut mutation = initial_mutation.to_vec();
+    for (value, offset) in mutation.iter_mut().zip(axis_offsets) {
+        *value += offset;
+    }
+    mutation
+}
+
+/// Mutation between neighboring cells of the current grid along X and Y, negative for axes swept
+/// in [`AxisDirection::Negative`].
+fn cell_scales<T>(state: &ViewerState<T>) -> (f64, f64) {
+    let scale = |axis: usize| {
+        let sign = AxisDirection::of_axis(&state.axis_directions, axis).sign();
+        state.all_scale * state.mutation_scale[axis] * sign
+    };
+    (scale(0), scale(1))
+}
+
+/// Configures `init_data` so the next redraw covers the current grid zoomed in `factor` times
+/// around `center`, given like in [`area_box`]. The current axis offsets are folded into
+/// [`InitData::initial_mutation`].
+pub fn zoom_to_box<T>(
+    state: &ViewerState<T>,
+    init_data: &mut InitData<T>,
+    center: Vec2,
+    factor: f64,
+) {
+    let (x_scale, y_scale) = cell_scales(state);
+
+    init_data.initial_mutation = origin_mutation(&state.initial_mutation, &state.axis_offsets);
+    init_data.axis_offsets.clear();
+    init_data.initial_mutation[0] += center.x as f64 * x_scale;
+    init_data.initial_mutation[1] -= center.y as f64 * y_scale;
+    // The current scales already include the aspect ratio
+    init_data.mutation_scale = state.mutation_scale.clone();
+    init_data.mutation_scale[1] *= init_data.aspect_ratio;
+    init_data.axis_directions = state.axis_directions.clone();
+    init_data.all_scale = state.all_scale / factor;
+    init_data.dimensions = state.samples.dimensions.clone();
+}
+
+/// Configures `init_data` so the next redraw fills a grid of the current dimensions with its
+/// [`area_box`], fitting the box width.
+pub fn zoom_to_area_box<T>(state: &ViewerState<T>, init_data: &mut InitData<T>) {
+    let (center, _) = area_box(state, init_data);
+    let (x_scale, _) = cell_scales(state);
+    let x_new_scale = (init_data.all_scale * init_data.axis_scales()[0]).abs();
+    let factor = (state.samples.dimensions[0] as f64 * x_scale.abs())
+        / (init_data.dimensions[0] as f64 * x_new_scale);
+    if !(factor.is_finite() && factor > 0.0) {
+        return;
+    }
+
+    zoom_to_box(state, init_data, center, factor);
+}
+
 pub fn visualize_area<T: ChaoticSystem>(
     state: Res<ViewerState<T>>,
     layer_data: Res<LayerData>,
     init_data: Res<InitData<T>>,
     mut area_gizmos: Gizmos<AreaGizmos>,
 ) {
-    let origin_x = state.initial_mutation[0];
-    let origin_y = state.initial_mutation[1];
-    let new_x = init_data.initial_mutation[0];
-    let new_y = init_data.initial_mutation[1];
-
-    let x_scale = state.all_scale * state.mutation_scale[0];
-    let y_scale = state.all_scale * state.mutation_scale[1];
-    let x_new_scale = init_data.all_scale * init_data.mutation_scale[0];
-    let y_new_scale = init_data.all_scale * init_data.mutation_scale[1];
+    let (center, half_size) = area_box(&state, &init_data);
 
-    let delta_x = new_x - origin_x;
-    let delta_y = origin_y - new_y;
+    // Sprites are centered on the grid, with image rows going down
+    let sizes = state.samples.dimensions.sizes();
+    let origin = init_data.grid_origin.cell(&state.samples.dimensions);
+    let origin_x = origin[0] as f32 + 0.5 - sizes[0] as f32 / 2.0;
+    let origin_y = sizes[1] as f32 / 2.0 - origin[1] as f32 - 0.5;
 
-    let center_x = (delta_x / x_scale) as f32;
-    let center_y = (delta_y / y_scale) as f32;
-
-    let center = Vec3::X * center_x + Vec3::Y * center_y;
+    let center = Vec3::X * (origin_x + center.x) + Vec3::Y * (origin_y + center.y);
     let height = Vec3::Z * layer_data.current_size();
 
     area_gizmos.line(center, center + height, Color::WHITE);
 
-    let x_h_range = (init_data.dimensions[0] as f64 / 2.0 * x_new_scale / x_scale) as f32;
-    let y_h_range = (init_data.dimensions[1] as f64 / 2.0 * y_new_scale / y_scale) as f32;
+    let x_h_range = half_size.x;
+    let y_h_range = half_size.y;
     let a = center + Vec3::X * x_h_range + Vec3::Y * y_h_range;
     let b = center + Vec3::X * x_h_range - Vec3::Y * y_h_range;
     let c = center - Vec3::X * x_h_range + Vec3::Y * y_h_range;
@@ -45,3 +117,173 @@ pub fn visualize_area<T: ChaoticSystem>(
     area_gizmos.line(c, c + height, Color::WHITE);
     area_gizmos.line(d, d + height, Color::WHITE);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chaotic::{Dimensions, Mandelbrot};
+
+    /// 8x6 grid, the configuration shared by the zoom tests.
+    fn zoom_init_data() -> InitData<Mandelbrot> {
+        InitData {
+            dimensions: Dimensions::new(vec![8, 6]),
+            ..default()
+        }
+    }
+
+    /// Asserts the origin cell of the grid configured by `init_data` starts from the parameters of
+    /// the current sample `offset` cells away from the origin, with Y pointing down.
+    fn assert_zoomed_origin(
+        state: &ViewerState<Mandelbrot>,
+        init_data: &InitData<Mandelbrot>,
+        offset: [usize; 2],
+    ) {
+        let origin = init_data.grid_origin.cell(&state.samples.dimensions);
+        let cell = [origin[0] + offset[0], origin[1] + offset[1]];
+        let index = state.samples.dimensions.pos_to_index(&cell);
+        let zoomed = init_data.init();
+        let zoomed_origin = zoomed.samples.dimensions.pos_to_index(&origin);
+        let error = zoomed.samples.samples[zoomed_origin]
+            .c
+            .distance(state.samples.samples[index].c);
+        assert!(error < 1e-12, "{}", error);
+    }
+
+    #[test]
+    fn test_zoom_to_box() {
+        let mut init_data = InitData {
+            mutation_scale: vec![1.0, 2.0],
+            ..zoom_init_data()
+        };
+        let state = init_data.init();
+
+        // Unchanged config covers exactly the current grid
+        assert_eq!(area_box(&state, &init_data), (Vec2::ZERO, vec2(4.0, 3.0)));
+
+        let center = vec2(2.0, -1.0);
+        zoom_to_box(&state, &mut init_data, center, 4.0);
+        let (zoomed_center, half_size) = area_box(&state, &init_data);
+        assert!(
+            (zoomed_center - center).length() < 1e-5,
+            "{}",
+            zoomed_center
+        );
+        assert!(
+            (half_size - vec2(1.0, 0.75)).length() < 1e-5,
+            "{}",
+            half_size
+        );
+
+        // The zoomed grid starts from the parameters of the sample at `center`
+        assert_zoomed_origin(&state, &init_data, [2, 1]);
+    }
+
+    #[test]
+    fn test_zoom_to_area_box() {
+        let mut init_data = zoom_init_data();
+        let state = init_data.init();
+
+        // A box 2 cells right and 1 down from the origin, on a 4x4 grid of quarter steps
+        init_data.initial_mutation[0] += 2.0 * init_data.all_scale;
+        init_data.initial_mutation[1] += init_data.all_scale;
+        init_data.all_scale /= 4.0;
+        init_data.dimensions = Dimensions::new(vec![4, 4]);
+        let (center, half_size) = area_box(&state, &init_data);
+        assert!((center - vec2(2.0, -1.0)).length() < 1e-5, "{}", center);
+        assert!(
+            (half_size - vec2(0.5, 0.5)).length() < 1e-5,
+            "{}",
+            half_size
+        );
+
+        // The box now spans the full width of a grid with the current dimensions
+        zoom_to_area_box(&state, &mut init_data);
+        assert_eq!(init_data.dimensions.sizes(), &[8, 6]);
+        let (zoomed_center, zoomed_half_size) = area_box(&state, &init_data);
+        assert!(
+            (zoomed_center - center).length() < 1e-5,
+            "{}",
+            zoomed_center
+        );
+        assert!(
+            (zoomed_half_size - vec2(0.5, 0.375)).length() < 1e-5,
+            "{}",
+            zoomed_half_size
+        );
+        assert_zoomed_origin(&state, &init_data, [2, 1]);
+    }
+
+    #[test]
+    fn test_zoom_to_box_with_offsets() {
+        let mut init_data = InitData {
+            axis_offsets: vec![0.5, -0.25],
+            ..zoom_init_data()
+        };
+        let state = init_data.init();
+        assert_eq!(area_box(&state, &init_data), (Vec2::ZERO, vec2(4.0, 3.0)));
+
+        // Moving the offsets moves the box, like moving the initial mutation
+        init_data.axis_offsets = vec![0.5 + 2.0 * init_data.all_scale, -0.25];
+        let (center, _) = area_box(&state, &init_data);
+        assert!((center - vec2(2.0, 0.0)).length() < 1e-5, "{}", center);
+        init_data.axis_offsets = state.axis_offsets.clone();
+
+        let center = vec2(2.0, -1.0);
+        zoom_to_box(&state, &mut init_data, center, 2.0);
+        assert!(init_data.axis_offsets.is_empty());
+        let (zoomed_center, _) = area_box(&state, &init_data);
+        assert!(
+            (zoomed_center - center).length() < 1e-5,
+            "{}",
+            zoomed_center
+        );
+        assert_zoomed_origin(&state, &init_data, [2, 1]);
+    }
+
+    #[test]
+    fn test_aspect_ratio() {
+        let mut init_data = InitData::<Mandelbrot> {
+            dimensions: Dimensions::new(vec![4, 4]),
+            aspect_ratio: 2.0,
+            ..default()
+        };
+        let state = init_data.init();
+
+        // A square grid covering a region twice as wide as high
+        let c = |x: usize, y: usize| {
+            state.samples.samples[state.samples.dimensions.pos_to_index(&[x, y])].c
+        };
+        let step = c(1, 1) - c(0, 0);
+        assert!((step.x - 2.0 * step.y).abs() < 1e-12, "{}", step);
+        assert_eq!(area_box(&state, &init_data), (Vec2::ZERO, vec2(2.0, 2.0)));
+
+        zoom_to_box(&state, &mut init_data, Vec2::ZERO, 2.0);
+        assert_eq!(init_data.mutation_scale, vec![1.0, 1.0]);
+        assert_eq!(area_box(&state, &init_data), (Vec2::ZERO, vec2(1.0, 1.0)));
+    }
+
+    #[test]
+    fn test_zoom_to_box_negative_direction() {
+        let mut init_data = InitData {
+            axis_directions: vec![AxisDirection::Negative],
+            ..zoom_init_data()
+        };
+        let state = init_data.init();
+        assert_eq!(area_box(&state, &init_data), (Vec2::ZERO, vec2(4.0, 3.0)));
+
+        let center = vec2(2.0, -1.0);
+        zoom_to_box(&state, &mut init_data, center, 2.0);
+        let (zoomed_center, half_size) = area_box(&state, &init_data);
+        assert!(
+            (zoomed_center - center).length() < 1e-5,
+            "{}",
+            zoomed_center
+        );
+        assert!(
+            (half_size - vec2(2.0, 1.5)).length() < 1e-5,
+            "{}",
+            half_size
+        );
+        assert_zoomed_origin(&state, &init_data, [2, 1]);
+    }
+}